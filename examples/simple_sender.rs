@@ -42,6 +42,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         }),
         target: Target::Token(args.device_token),
         fcm_options: None,
+        excluded_platforms: Vec::new(),
         android: None,
         apns: None,
         webpush: None,
@@ -1,4 +1,6 @@
-use serde::Serialize;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::{
@@ -6,7 +8,7 @@ use super::{
     android_notification::AndroidNotification,
 };
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 /// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages?authuser=0#androidconfig>
 pub struct AndroidConfig {
     /// An identifier of a group of messages that can be collapsed, so that only the last message gets
@@ -18,10 +20,15 @@ pub struct AndroidConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub priority: Option<AndroidMessagePriority>,
 
-    /// How long (in seconds) the message should be kept in FCM storage if the device is offline.
-    /// Duration format: <https://developers.google.com/protocol-buffers/docs/reference/google.protobuf?authuser=0#google.protobuf.Duration>
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ttl: Option<String>,
+    /// How long the message should be kept in FCM storage if the device is offline. The maximum is 4 weeks.
+    /// Serialized in duration format: <https://developers.google.com/protocol-buffers/docs/reference/google.protobuf?authuser=0#google.protobuf.Duration>
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::duration::serialize_option",
+        deserialize_with = "crate::duration::deserialize_option"
+    )]
+    pub ttl: Option<Duration>,
 
     /// Package name of the application where the registration token must match in order to receive the message.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -43,3 +50,77 @@ pub struct AndroidConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub direct_boot_ok: Option<bool>,
 }
+
+impl AndroidConfig {
+    pub fn builder() -> AndroidConfigBuilder {
+        AndroidConfigBuilder::default()
+    }
+}
+
+/// Builder for [AndroidConfig].
+///
+/// ```rust
+/// use fcm::message::{AndroidConfig, AndroidMessagePriority};
+///
+/// let config = AndroidConfig::builder()
+///     .collapse_key("sync")
+///     .priority(AndroidMessagePriority::High)
+///     .direct_boot_ok(true)
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct AndroidConfigBuilder {
+    config: AndroidConfig,
+}
+
+impl AndroidConfigBuilder {
+    /// Messages with the same collapse key replace each other while the
+    /// device is offline, so only the last one is delivered.
+    pub fn collapse_key(mut self, collapse_key: impl Into<String>) -> Self {
+        self.config.collapse_key = Some(collapse_key.into());
+        self
+    }
+
+    pub fn priority(mut self, priority: AndroidMessagePriority) -> Self {
+        self.config.priority = Some(priority);
+        self
+    }
+
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.config.ttl = Some(ttl);
+        self
+    }
+
+    /// The message is delivered only if the registration token belongs to
+    /// an app with this package name.
+    pub fn restricted_package_name(mut self, restricted_package_name: impl Into<String>) -> Self {
+        self.config.restricted_package_name = Some(restricted_package_name.into());
+        self
+    }
+
+    pub fn data(mut self, data: Value) -> Self {
+        self.config.data = Some(data);
+        self
+    }
+
+    pub fn notification(mut self, notification: AndroidNotification) -> Self {
+        self.config.notification = Some(notification);
+        self
+    }
+
+    pub fn fcm_options(mut self, fcm_options: AndroidFcmOptions) -> Self {
+        self.config.fcm_options = Some(fcm_options);
+        self
+    }
+
+    /// Allow delivering the message while the device is in direct boot
+    /// mode, before the user has unlocked it.
+    pub fn direct_boot_ok(mut self, direct_boot_ok: bool) -> Self {
+        self.config.direct_boot_ok = Some(direct_boot_ok);
+        self
+    }
+
+    pub fn build(self) -> AndroidConfig {
+        self.config
+    }
+}
@@ -1,8 +1,16 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Serialize)]
+use crate::message::AnalyticsLabel;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages?authuser=0#androidconfig>
 pub struct AndroidFcmOptions {
     /// Label associated with the message's analytics data.
-    pub analytics_label: String,
+    pub analytics_label: AnalyticsLabel,
+}
+
+impl AndroidFcmOptions {
+    pub fn new(analytics_label: AnalyticsLabel) -> Self {
+        Self { analytics_label }
+    }
 }
@@ -1,8 +1,8 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use super::{light_settings::LightSettings, notification_priority::NotificationPriority, visibility::Visibility};
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 /// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages?authuser=0#androidnotification>
 pub struct AndroidNotification {
     /// The notification's title.
@@ -110,4 +110,92 @@ pub struct AndroidNotification {
     /// Contains the URL of an image that is going to be displayed in a notification.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image: Option<String>,
+
+    /// If set to true, the notification is not proxied to the app on supported devices when the app is in the
+    /// background.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bypass_proxy_notification: Option<bool>,
+}
+
+impl AndroidNotification {
+    pub fn builder() -> AndroidNotificationBuilder {
+        AndroidNotificationBuilder::default()
+    }
+}
+
+/// Builder for [AndroidNotification] with the most common fields. Other
+/// fields can be set on the built struct.
+///
+/// ```rust
+/// use fcm::message::AndroidNotification;
+///
+/// let notification = AndroidNotification::builder()
+///     .title_loc("order_shipped_title", vec![])
+///     .body_loc("order_shipped_body", vec!["1234".to_string()])
+///     .channel_id("orders")
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct AndroidNotificationBuilder {
+    notification: AndroidNotification,
+}
+
+impl AndroidNotificationBuilder {
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.notification.title = Some(title.into());
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.notification.body = Some(body.into());
+        self
+    }
+
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.notification.icon = Some(icon.into());
+        self
+    }
+
+    pub fn sound(mut self, sound: impl Into<String>) -> Self {
+        self.notification.sound = Some(sound.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.notification.tag = Some(tag.into());
+        self
+    }
+
+    pub fn click_action(mut self, click_action: impl Into<String>) -> Self {
+        self.notification.click_action = Some(click_action.into());
+        self
+    }
+
+    pub fn channel_id(mut self, channel_id: impl Into<String>) -> Self {
+        self.notification.channel_id = Some(channel_id.into());
+        self
+    }
+
+    pub fn image(mut self, image: impl Into<String>) -> Self {
+        self.notification.image = Some(image.into());
+        self
+    }
+
+    /// Localize the title with the string resource `key` formatted with `args`.
+    pub fn title_loc(mut self, key: impl Into<String>, args: Vec<String>) -> Self {
+        self.notification.title_loc_key = Some(key.into());
+        self.notification.title_loc_args = Some(args);
+        self
+    }
+
+    /// Localize the body with the string resource `key` formatted with `args`.
+    pub fn body_loc(mut self, key: impl Into<String>, args: Vec<String>) -> Self {
+        self.notification.body_loc_key = Some(key.into());
+        self.notification.body_loc_args = Some(args);
+        self
+    }
+
+    pub fn build(self) -> AndroidNotification {
+        self.notification
+    }
 }
@@ -6,3 +6,6 @@ pub mod color;
 pub mod light_settings;
 pub mod notification_priority;
 pub mod visibility;
+
+#[cfg(test)]
+mod tests;
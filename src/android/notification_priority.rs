@@ -1,7 +1,7 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "UPPERCASE")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 /// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages?authuser=0#notificationpriority>
 pub enum NotificationPriority {
     PriorityUnspecified,
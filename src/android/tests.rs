@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use crate::message::{
+    AndroidConfig, AndroidMessagePriority, AndroidNotification, Color, LightSettings, NotificationPriority, Visibility,
+};
+use serde_json::json;
+
+#[test]
+fn should_render_android_enums_in_api_format() {
+    let config = AndroidConfig {
+        priority: Some(AndroidMessagePriority::High),
+        notification: Some(AndroidNotification {
+            visibility: Some(Visibility::VisibilityUnspecified),
+            notification_priority: Some(NotificationPriority::PriorityMin),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let payload = serde_json::to_string(&config).unwrap();
+
+    let expected_payload = json!({
+        "priority": "HIGH",
+        "notification": {
+            "notification_priority": "PRIORITY_MIN",
+            "visibility": "VISIBILITY_UNSPECIFIED",
+        },
+    })
+    .to_string();
+
+    assert_eq!(expected_payload, payload);
+}
+
+#[test]
+fn should_be_able_to_render_a_full_android_notification_to_json() {
+    let notification = AndroidNotification {
+        channel_id: Some("orders".to_string()),
+        notification_count: Some(3),
+        light_settings: Some(LightSettings {
+            color: Color {
+                red: 1.0,
+                green: 0.0,
+                blue: 0.0,
+                alpha: 1.0,
+            },
+            light_on_duration: "1s".to_string(),
+            light_off_duration: "2s".to_string(),
+        }),
+        vibrate_timings: Some(vec!["0.5s".to_string()]),
+        visibility: Some(Visibility::Public),
+        notification_priority: Some(NotificationPriority::PriorityHigh),
+        sticky: Some(true),
+        event_time: Some("2024-05-26T10:00:00Z".to_string()),
+        local_only: Some(false),
+        default_sound: Some(true),
+        bypass_proxy_notification: Some(true),
+        ..Default::default()
+    };
+
+    let payload = serde_json::to_string(&notification).unwrap();
+
+    let expected_payload = json!({
+        "channel_id": "orders",
+        "sticky": true,
+        "event_time": "2024-05-26T10:00:00Z",
+        "local_only": false,
+        "notification_priority": "PRIORITY_HIGH",
+        "default_sound": true,
+        "vibrate_timings": ["0.5s"],
+        "visibility": "PUBLIC",
+        "notification_count": 3,
+        "light_settings": {
+            "color": {
+                "red": 1.0,
+                "green": 0.0,
+                "blue": 0.0,
+                "alpha": 1.0,
+            },
+            "light_on_duration": "1s",
+            "light_off_duration": "2s",
+        },
+        "bypass_proxy_notification": true,
+    })
+    .to_string();
+
+    assert_eq!(expected_payload, payload);
+}
+
+#[test]
+fn should_render_android_ttl_as_duration_string() {
+    let config = AndroidConfig {
+        ttl: Some(Duration::from_millis(3_600_500)),
+        ..Default::default()
+    };
+
+    let payload = serde_json::to_string(&config).unwrap();
+
+    let expected_payload = json!({
+        "ttl": "3600.500s",
+    })
+    .to_string();
+
+    assert_eq!(expected_payload, payload);
+}
+
+#[test]
+fn should_build_android_config() {
+    let config = AndroidConfig::builder()
+        .collapse_key("sync")
+        .restricted_package_name("com.example.app")
+        .direct_boot_ok(true)
+        .build();
+
+    let payload = serde_json::to_string(&config).unwrap();
+
+    let expected_payload = json!({
+        "collapse_key": "sync",
+        "restricted_package_name": "com.example.app",
+        "direct_boot_ok": true,
+    })
+    .to_string();
+
+    assert_eq!(expected_payload, payload);
+}
@@ -1,9 +1,9 @@
-use serde::Serialize;
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-use super::apns_fcm_options::ApnsFcmOptions;
+use super::{apns_fcm_options::ApnsFcmOptions, apns_headers::ApnsHeaders, aps::Aps};
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 /// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages?authuser=0#apnsconfig>
 pub struct ApnsConfig {
     /// HTTP request headers defined in Apple Push Notification Service.
@@ -17,4 +17,82 @@ pub struct ApnsConfig {
     /// Options for features provided by the FCM SDK for iOS.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fcm_options: Option<ApnsFcmOptions>,
+
+    /// APNs push token of a Live Activity, used to start, update or end it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub live_activity_token: Option<String>,
+}
+
+impl ApnsConfig {
+    pub fn builder() -> ApnsConfigBuilder {
+        ApnsConfigBuilder::default()
+    }
+}
+
+/// Builder for [ApnsConfig] which creates the `payload` from a typed [Aps]
+/// dictionary and custom keys.
+///
+/// ```rust
+/// use fcm::message::{ApnsConfig, Aps, ApsAlert};
+///
+/// let config = ApnsConfig::builder()
+///     .aps(Aps::builder().alert(ApsAlert::builder().title("Hello").build()).build())
+///     .custom_data("order_id", "1234")
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct ApnsConfigBuilder {
+    headers: Option<Value>,
+    aps: Option<Aps>,
+    custom_data: Map<String, Value>,
+    fcm_options: Option<ApnsFcmOptions>,
+    live_activity_token: Option<String>,
+}
+
+impl ApnsConfigBuilder {
+    pub fn headers(mut self, headers: ApnsHeaders) -> Self {
+        self.headers = Some(serde_json::to_value(headers).unwrap_or(Value::Null));
+        self
+    }
+
+    /// Set headers as a JSON object, for headers which [ApnsHeaders] does
+    /// not support.
+    pub fn raw_headers(mut self, headers: Value) -> Self {
+        self.headers = Some(headers);
+        self
+    }
+
+    pub fn aps(mut self, aps: Aps) -> Self {
+        self.aps = Some(aps);
+        self
+    }
+
+    /// Add a custom key to the payload, next to the `aps` dictionary.
+    pub fn custom_data(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.custom_data.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn fcm_options(mut self, fcm_options: ApnsFcmOptions) -> Self {
+        self.fcm_options = Some(fcm_options);
+        self
+    }
+
+    pub fn live_activity_token(mut self, live_activity_token: impl Into<String>) -> Self {
+        self.live_activity_token = Some(live_activity_token.into());
+        self
+    }
+
+    pub fn build(self) -> ApnsConfig {
+        let mut payload = self.custom_data;
+        if let Some(aps) = self.aps {
+            payload.insert("aps".to_string(), serde_json::to_value(aps).unwrap_or(Value::Null));
+        }
+        ApnsConfig {
+            headers: self.headers,
+            payload: (!payload.is_empty()).then_some(Value::Object(payload)),
+            fcm_options: self.fcm_options,
+            live_activity_token: self.live_activity_token,
+        }
+    }
 }
@@ -1,10 +1,12 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Serialize)]
+use crate::message::AnalyticsLabel;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 /// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages?authuser=0#apnsfcmoptions>
 pub struct ApnsFcmOptions {
     /// Label associated with the message's analytics data.
-    pub analytics_label: Option<String>,
+    pub analytics_label: Option<AnalyticsLabel>,
 
     /// Contains the URL of an image that is going to be displayed in a notification.
     pub image: Option<String>,
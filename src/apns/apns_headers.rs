@@ -0,0 +1,161 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+/// Value of the `apns-push-type` header.
+/// <https://developer.apple.com/documentation/usernotifications/sending-notification-requests-to-apns#Send-a-POST-request-to-APNs>
+pub enum ApnsPushType {
+    Alert,
+    Background,
+    Location,
+    Voip,
+    Complication,
+    FileProvider,
+    Mdm,
+    LiveActivity,
+    PushToTalk,
+    Widgets,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Value of the `apns-priority` header.
+pub enum ApnsPriority {
+    /// Send the notification immediately (10).
+    Immediate,
+    /// Send the notification based on power considerations on the device (5).
+    PowerConsiderate,
+    /// Prioritize the device's power considerations over all other factors (1).
+    Low,
+}
+
+impl ApnsPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApnsPriority::Immediate => "10",
+            ApnsPriority::PowerConsiderate => "5",
+            ApnsPriority::Low => "1",
+        }
+    }
+}
+
+impl Serialize for ApnsPriority {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_str(self.as_str())
+    }
+}
+
+fn serialize_to_string<S, T>(value: &Option<T>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ToString,
+{
+    match value {
+        Some(value) => s.serialize_str(&value.to_string()),
+        None => s.serialize_none(),
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+/// Typed APNs request headers which serialize into `apns.headers`.
+/// <https://developer.apple.com/documentation/usernotifications/sending-notification-requests-to-apns#Send-a-POST-request-to-APNs>
+pub struct ApnsHeaders {
+    /// The type of the notification. Required for watchOS 6 and later, recommended for all platforms.
+    #[serde(rename = "apns-push-type", skip_serializing_if = "Option::is_none")]
+    pub push_type: Option<ApnsPushType>,
+
+    /// The priority of the notification.
+    #[serde(rename = "apns-priority", skip_serializing_if = "Option::is_none")]
+    pub priority: Option<ApnsPriority>,
+
+    /// UNIX epoch time in seconds after which the notification is no longer valid. Zero means that
+    /// APNs attempts the delivery only once.
+    #[serde(
+        rename = "apns-expiration",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_to_string"
+    )]
+    pub expiration: Option<u64>,
+
+    /// Identifier used to merge multiple notifications into a single notification. At most 64 bytes.
+    #[serde(rename = "apns-collapse-id", skip_serializing_if = "Option::is_none")]
+    pub collapse_id: Option<String>,
+
+    /// The topic for the notification, usually the app's bundle ID.
+    #[serde(rename = "apns-topic", skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+}
+
+impl ApnsHeaders {
+    pub fn builder() -> ApnsHeadersBuilder {
+        ApnsHeadersBuilder::default()
+    }
+}
+
+/// Builder for [ApnsHeaders].
+///
+/// ```rust
+/// use std::time::Duration;
+/// use fcm::message::{ApnsHeaders, ApnsPriority, ApnsPushType};
+///
+/// let headers = ApnsHeaders::builder()
+///     .push_type(ApnsPushType::Background)
+///     .priority(ApnsPriority::PowerConsiderate)
+///     .expires_in(Duration::from_secs(60 * 60))
+///     .collapse_id("sync")
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct ApnsHeadersBuilder {
+    headers: ApnsHeaders,
+}
+
+impl ApnsHeadersBuilder {
+    pub fn push_type(mut self, push_type: ApnsPushType) -> Self {
+        self.headers.push_type = Some(push_type);
+        self
+    }
+
+    pub fn priority(mut self, priority: ApnsPriority) -> Self {
+        self.headers.priority = Some(priority);
+        self
+    }
+
+    /// Notification is no longer valid after `expiration`.
+    pub fn expiration(mut self, expiration: SystemTime) -> Self {
+        let seconds = expiration.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        // Zero has the special meaning of delivering only once.
+        self.headers.expiration = Some(seconds.max(1));
+        self
+    }
+
+    /// Notification is no longer valid after `ttl` from now.
+    pub fn expires_in(self, ttl: Duration) -> Self {
+        self.expiration(crate::client::rt::now() + ttl)
+    }
+
+    /// APNs attempts to deliver the notification only once and does not
+    /// store it.
+    pub fn expire_immediately(mut self) -> Self {
+        self.headers.expiration = Some(0);
+        self
+    }
+
+    pub fn collapse_id(mut self, collapse_id: impl Into<String>) -> Self {
+        self.headers.collapse_id = Some(collapse_id.into());
+        self
+    }
+
+    pub fn topic(mut self, topic: impl Into<String>) -> Self {
+        self.headers.topic = Some(topic.into());
+        self
+    }
+
+    pub fn build(self) -> ApnsHeaders {
+        self.headers
+    }
+}
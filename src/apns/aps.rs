@@ -0,0 +1,291 @@
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+use super::sound::Sound;
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// APNs expects flags such as `content-available` to be the number 1.
+fn serialize_flag<S>(_: &bool, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_u8(1)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+/// <https://developer.apple.com/documentation/usernotifications/unnotificationinterruptionlevel>
+pub enum InterruptionLevel {
+    Passive,
+    Active,
+    TimeSensitive,
+    Critical,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+/// The `aps` dictionary of the APNs payload.
+/// <https://developer.apple.com/documentation/usernotifications/generating-a-remote-notification#Payload-key-reference>
+pub struct Aps {
+    /// The information for displaying an alert.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert: Option<ApsAlert>,
+
+    /// The number to display in a badge on the app icon. Zero removes the badge.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub badge: Option<u32>,
+
+    /// The sound to play, either a sound file name or a critical alert sound.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sound: Option<Sound>,
+
+    /// The notification's type. Must match an identifier registered by the app.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+
+    /// An app-specific identifier for grouping related notifications.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+
+    /// Background update notification flag.
+    #[serde(skip_serializing_if = "is_false", serialize_with = "serialize_flag")]
+    pub content_available: bool,
+
+    /// Notification service app extension flag.
+    #[serde(skip_serializing_if = "is_false", serialize_with = "serialize_flag")]
+    pub mutable_content: bool,
+
+    /// The importance and delivery timing of the notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interruption_level: Option<InterruptionLevel>,
+
+    /// The relevance score, between 0 and 1, used to sort notifications in a summary.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relevance_score: Option<f64>,
+
+    /// The identifier of the window brought forward when the notification is opened.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_content_id: Option<String>,
+
+    /// Live Activity action.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<LiveActivityEvent>,
+
+    /// UNIX epoch time in seconds when the Live Activity update was sent. Required for Live Activities.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+
+    /// Updated dynamic content of the Live Activity. Must match the app's `ContentState` type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_state: Option<Value>,
+
+    /// UNIX epoch time in seconds after which the Live Activity is displayed as outdated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale_date: Option<u64>,
+
+    /// UNIX epoch time in seconds when an ended Live Activity is removed from the Lock Screen.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dismissal_date: Option<u64>,
+
+    /// Name of the app's `ActivityAttributes` type, required when starting a Live Activity.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes_type: Option<String>,
+
+    /// Static attributes of the Live Activity, required when starting it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+/// <https://developer.apple.com/documentation/activitykit/starting-and-updating-live-activities-with-activitykit-push-notifications>
+pub enum LiveActivityEvent {
+    Start,
+    Update,
+    End,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+/// The `alert` dictionary of the `aps` dictionary.
+/// <https://developer.apple.com/documentation/usernotifications/generating-a-remote-notification#Payload-key-reference>
+pub struct ApsAlert {
+    /// The title of the notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Additional information that explains the purpose of the notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+
+    /// The content of the alert message.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+
+    /// The name of the launch image file to display.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch_image: Option<String>,
+
+    /// The key for a localized title string in the app's Localizable.strings file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_loc_key: Option<String>,
+
+    /// Replacement values for the variables in the title string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_loc_args: Option<Vec<String>>,
+
+    /// The key for a localized subtitle string in the app's Localizable.strings file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle_loc_key: Option<String>,
+
+    /// Replacement values for the variables in the subtitle string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle_loc_args: Option<Vec<String>>,
+
+    /// The key for a localized message string in the app's Localizable.strings file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loc_key: Option<String>,
+
+    /// Replacement values for the variables in the message string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loc_args: Option<Vec<String>>,
+}
+
+impl Aps {
+    pub fn builder() -> ApsBuilder {
+        ApsBuilder::default()
+    }
+}
+
+/// Builder for [Aps].
+///
+/// ```rust
+/// use fcm::message::{Aps, ApsAlert, InterruptionLevel};
+///
+/// let aps = Aps::builder()
+///     .alert(ApsAlert::builder().title("Order shipped").body("Arrives tomorrow").build())
+///     .badge(1)
+///     .interruption_level(InterruptionLevel::TimeSensitive)
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct ApsBuilder {
+    aps: Aps,
+}
+
+impl ApsBuilder {
+    pub fn alert(mut self, alert: ApsAlert) -> Self {
+        self.aps.alert = Some(alert);
+        self
+    }
+
+    pub fn badge(mut self, badge: u32) -> Self {
+        self.aps.badge = Some(badge);
+        self
+    }
+
+    /// Sound file name or [Sound::critical].
+    pub fn sound(mut self, sound: impl Into<Sound>) -> Self {
+        self.aps.sound = Some(sound.into());
+        self
+    }
+
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.aps.category = Some(category.into());
+        self
+    }
+
+    pub fn thread_id(mut self, thread_id: impl Into<String>) -> Self {
+        self.aps.thread_id = Some(thread_id.into());
+        self
+    }
+
+    pub fn content_available(mut self, content_available: bool) -> Self {
+        self.aps.content_available = content_available;
+        self
+    }
+
+    pub fn mutable_content(mut self, mutable_content: bool) -> Self {
+        self.aps.mutable_content = mutable_content;
+        self
+    }
+
+    pub fn interruption_level(mut self, interruption_level: InterruptionLevel) -> Self {
+        self.aps.interruption_level = Some(interruption_level);
+        self
+    }
+
+    /// Relevance score between 0 and 1. Values outside the range are clamped.
+    pub fn relevance_score(mut self, relevance_score: f64) -> Self {
+        self.aps.relevance_score = Some(relevance_score.clamp(0.0, 1.0));
+        self
+    }
+
+    pub fn target_content_id(mut self, target_content_id: impl Into<String>) -> Self {
+        self.aps.target_content_id = Some(target_content_id.into());
+        self
+    }
+
+    pub fn build(self) -> Aps {
+        self.aps
+    }
+}
+
+impl ApsAlert {
+    pub fn builder() -> ApsAlertBuilder {
+        ApsAlertBuilder::default()
+    }
+}
+
+/// Builder for [ApsAlert].
+#[derive(Debug, Default)]
+pub struct ApsAlertBuilder {
+    alert: ApsAlert,
+}
+
+impl ApsAlertBuilder {
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.alert.title = Some(title.into());
+        self
+    }
+
+    pub fn subtitle(mut self, subtitle: impl Into<String>) -> Self {
+        self.alert.subtitle = Some(subtitle.into());
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.alert.body = Some(body.into());
+        self
+    }
+
+    pub fn launch_image(mut self, launch_image: impl Into<String>) -> Self {
+        self.alert.launch_image = Some(launch_image.into());
+        self
+    }
+
+    pub fn title_loc(mut self, key: impl Into<String>, args: Vec<String>) -> Self {
+        self.alert.title_loc_key = Some(key.into());
+        self.alert.title_loc_args = Some(args);
+        self
+    }
+
+    pub fn subtitle_loc(mut self, key: impl Into<String>, args: Vec<String>) -> Self {
+        self.alert.subtitle_loc_key = Some(key.into());
+        self.alert.subtitle_loc_args = Some(args);
+        self
+    }
+
+    pub fn loc(mut self, key: impl Into<String>, args: Vec<String>) -> Self {
+        self.alert.loc_key = Some(key.into());
+        self.alert.loc_args = Some(args);
+        self
+    }
+
+    pub fn build(self) -> ApsAlert {
+        self.alert
+    }
+}
@@ -0,0 +1,152 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{
+    apns_config::ApnsConfig,
+    apns_headers::{ApnsHeaders, ApnsPriority, ApnsPushType},
+    aps::{Aps, ApsAlert, LiveActivityEvent},
+};
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Builder for an [ApnsConfig] which starts, updates or ends a Live Activity.
+///
+/// The message containing the configuration must still target the FCM
+/// registration token of the device.
+///
+/// ```rust
+/// use fcm::message::{LiveActivityUpdate, Message, Target};
+/// use serde_json::json;
+///
+/// let apns = LiveActivityUpdate::update("live-activity-push-token", json!({ "score": "2-1" }))
+///     .bundle_id("com.example.app")
+///     .build();
+///
+/// let message = Message::builder(Target::Token("fcm-token".to_string()))
+///     .apns(apns)
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct LiveActivityUpdate {
+    live_activity_token: String,
+    event: LiveActivityEvent,
+    content_state: Value,
+    bundle_id: Option<String>,
+    priority: ApnsPriority,
+    timestamp: SystemTime,
+    stale_date: Option<SystemTime>,
+    dismissal_date: Option<SystemTime>,
+    alert: Option<ApsAlert>,
+    attributes: Option<(String, Value)>,
+}
+
+impl LiveActivityUpdate {
+    fn new(live_activity_token: impl Into<String>, event: LiveActivityEvent, content_state: impl Serialize) -> Self {
+        Self {
+            live_activity_token: live_activity_token.into(),
+            event,
+            content_state: serde_json::to_value(content_state).unwrap_or(Value::Null),
+            bundle_id: None,
+            priority: ApnsPriority::Immediate,
+            timestamp: crate::client::rt::now(),
+            stale_date: None,
+            dismissal_date: None,
+            alert: None,
+            attributes: None,
+        }
+    }
+
+    /// Start a Live Activity with a push-to-start token. `attributes_type`
+    /// is the name of the app's `ActivityAttributes` type.
+    pub fn start(
+        push_to_start_token: impl Into<String>,
+        attributes_type: impl Into<String>,
+        attributes: impl Serialize,
+        content_state: impl Serialize,
+    ) -> Self {
+        let mut update = Self::new(push_to_start_token, LiveActivityEvent::Start, content_state);
+        update.attributes = Some((
+            attributes_type.into(),
+            serde_json::to_value(attributes).unwrap_or(Value::Null),
+        ));
+        update
+    }
+
+    pub fn update(live_activity_token: impl Into<String>, content_state: impl Serialize) -> Self {
+        Self::new(live_activity_token, LiveActivityEvent::Update, content_state)
+    }
+
+    pub fn end(live_activity_token: impl Into<String>, content_state: impl Serialize) -> Self {
+        Self::new(live_activity_token, LiveActivityEvent::End, content_state)
+    }
+
+    /// App bundle ID. Sets `apns-topic` to `<bundle_id>.push-type.liveactivity`.
+    pub fn bundle_id(mut self, bundle_id: impl Into<String>) -> Self {
+        self.bundle_id = Some(bundle_id.into());
+        self
+    }
+
+    /// Default is [ApnsPriority::Immediate]. Apple budgets high priority
+    /// Live Activity updates, so frequent updates should use
+    /// [ApnsPriority::PowerConsiderate].
+    pub fn priority(mut self, priority: ApnsPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Time of the update. Default is the time when the update was created.
+    pub fn timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn stale_date(mut self, stale_date: SystemTime) -> Self {
+        self.stale_date = Some(stale_date);
+        self
+    }
+
+    /// When the ended Live Activity is removed from the Lock Screen.
+    pub fn dismissal_date(mut self, dismissal_date: SystemTime) -> Self {
+        self.dismissal_date = Some(dismissal_date);
+        self
+    }
+
+    /// Alert shown to the user together with the update.
+    pub fn alert(mut self, alert: ApsAlert) -> Self {
+        self.alert = Some(alert);
+        self
+    }
+
+    pub fn build(self) -> ApnsConfig {
+        let headers = ApnsHeaders {
+            push_type: Some(ApnsPushType::LiveActivity),
+            priority: Some(self.priority),
+            topic: self
+                .bundle_id
+                .map(|bundle_id| format!("{}.push-type.liveactivity", bundle_id)),
+            ..Default::default()
+        };
+        let (attributes_type, attributes) = self.attributes.unzip();
+        let aps = Aps {
+            alert: self.alert,
+            event: Some(self.event),
+            timestamp: Some(unix_time(self.timestamp)),
+            content_state: Some(self.content_state),
+            stale_date: self.stale_date.map(unix_time),
+            dismissal_date: self.dismissal_date.map(unix_time),
+            attributes_type,
+            attributes,
+            ..Default::default()
+        };
+
+        ApnsConfig::builder()
+            .headers(headers)
+            .aps(aps)
+            .live_activity_token(self.live_activity_token)
+            .build()
+    }
+}
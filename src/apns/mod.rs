@@ -1,2 +1,9 @@
 pub mod apns_config;
 pub mod apns_fcm_options;
+pub mod apns_headers;
+pub mod aps;
+pub mod live_activity;
+pub mod sound;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,80 @@
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum SoundError {
+    #[error("Critical alert volume must be between 0.0 and 1.0, got {0}")]
+    InvalidVolume(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The `sound` of the `aps` dictionary.
+/// <https://developer.apple.com/documentation/usernotifications/generating-a-remote-notification#Payload-key-reference>
+pub enum Sound {
+    /// The name of a sound file in the app's main bundle or Library/Sounds
+    /// folder, or `"default"` for the system sound.
+    Named(String),
+    /// A critical alert sound, which plays even when the device is muted.
+    /// Requires the critical alerts entitlement. Built with [Sound::critical].
+    Critical(CriticalSound),
+}
+
+/// Sound of [Sound::Critical], whose volume is checked by [Sound::critical].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CriticalSound {
+    name: String,
+    volume: f64,
+}
+
+impl CriticalSound {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn volume(&self) -> f64 {
+        self.volume
+    }
+}
+
+impl Sound {
+    /// Critical alert sound with `volume` between 0.0 (silent) and 1.0 (full volume).
+    pub fn critical(name: impl Into<String>, volume: f64) -> Result<Self, SoundError> {
+        if !(0.0..=1.0).contains(&volume) {
+            return Err(SoundError::InvalidVolume(volume));
+        }
+        Ok(Sound::Critical(CriticalSound {
+            name: name.into(),
+            volume,
+        }))
+    }
+}
+
+impl From<&str> for Sound {
+    fn from(name: &str) -> Self {
+        Sound::Named(name.to_string())
+    }
+}
+
+impl From<String> for Sound {
+    fn from(name: String) -> Self {
+        Sound::Named(name)
+    }
+}
+
+impl Serialize for Sound {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Sound::Named(name) => s.serialize_str(name),
+            Sound::Critical(sound) => {
+                let mut map = s.serialize_map(Some(3))?;
+                map.serialize_entry("critical", &1)?;
+                map.serialize_entry("name", &sound.name)?;
+                map.serialize_entry("volume", &sound.volume)?;
+                map.end()
+            }
+        }
+    }
+}
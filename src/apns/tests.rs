@@ -0,0 +1,177 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::message::{
+    ApnsConfig, ApnsHeaders, ApnsPriority, ApnsPushType, Aps, ApsAlert, InterruptionLevel, LiveActivityUpdate, Sound,
+    SoundError,
+};
+use serde_json::json;
+
+#[test]
+fn should_render_typed_aps_into_apns_payload() {
+    let config = ApnsConfig::builder()
+        .aps(
+            Aps::builder()
+                .alert(
+                    ApsAlert::builder()
+                        .title("Order shipped")
+                        .subtitle("Order #1234")
+                        .loc("ORDER_SHIPPED_BODY", vec!["1234".to_string()])
+                        .build(),
+                )
+                .badge(3)
+                .sound("default")
+                .content_available(true)
+                .mutable_content(true)
+                .category("ORDER")
+                .thread_id("orders")
+                .interruption_level(InterruptionLevel::TimeSensitive)
+                .relevance_score(0.5)
+                .target_content_id("order-1234")
+                .build(),
+        )
+        .custom_data("order_id", "1234")
+        .build();
+
+    let payload = serde_json::to_value(&config).unwrap();
+
+    let expected_payload = json!({
+        "payload": {
+            "order_id": "1234",
+            "aps": {
+                "alert": {
+                    "title": "Order shipped",
+                    "subtitle": "Order #1234",
+                    "loc-key": "ORDER_SHIPPED_BODY",
+                    "loc-args": ["1234"],
+                },
+                "badge": 3,
+                "sound": "default",
+                "category": "ORDER",
+                "thread-id": "orders",
+                "content-available": 1,
+                "mutable-content": 1,
+                "interruption-level": "time-sensitive",
+                "relevance-score": 0.5,
+                "target-content-id": "order-1234",
+            },
+        },
+    });
+
+    assert_eq!(expected_payload, payload);
+}
+
+#[test]
+fn should_leave_out_unset_aps_flags() {
+    let config = ApnsConfig::builder().aps(Aps::default()).build();
+
+    let payload = serde_json::to_value(&config).unwrap();
+
+    assert_eq!(json!({ "payload": { "aps": {} } }), payload);
+}
+
+#[test]
+fn should_render_typed_apns_headers() {
+    let config = ApnsConfig::builder()
+        .headers(
+            ApnsHeaders::builder()
+                .push_type(ApnsPushType::LiveActivity)
+                .priority(ApnsPriority::Immediate)
+                .expiration(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+                .collapse_id("score")
+                .topic("com.example.app.push-type.liveactivity")
+                .build(),
+        )
+        .build();
+
+    let payload = serde_json::to_value(&config).unwrap();
+
+    let expected_payload = json!({
+        "headers": {
+            "apns-push-type": "liveactivity",
+            "apns-priority": "10",
+            "apns-expiration": "1700000000",
+            "apns-collapse-id": "score",
+            "apns-topic": "com.example.app.push-type.liveactivity",
+        },
+    });
+
+    assert_eq!(expected_payload, payload);
+}
+
+#[test]
+fn should_render_immediate_apns_expiration() {
+    let headers = ApnsHeaders::builder().expire_immediately().build();
+
+    assert_eq!(
+        json!({ "apns-expiration": "0" }),
+        serde_json::to_value(&headers).unwrap()
+    );
+}
+
+#[test]
+fn should_render_live_activity_update() {
+    let at = |seconds| UNIX_EPOCH + Duration::from_secs(seconds);
+    let config = LiveActivityUpdate::end("activity-token", json!({ "score": "3-1" }))
+        .bundle_id("com.example.app")
+        .timestamp(at(1_700_000_000))
+        .stale_date(at(1_700_000_600))
+        .dismissal_date(at(1_700_003_600))
+        .build();
+
+    let payload = serde_json::to_value(&config).unwrap();
+
+    let expected_payload = json!({
+        "headers": {
+            "apns-push-type": "liveactivity",
+            "apns-priority": "10",
+            "apns-topic": "com.example.app.push-type.liveactivity",
+        },
+        "payload": {
+            "aps": {
+                "event": "end",
+                "timestamp": 1_700_000_000,
+                "content-state": { "score": "3-1" },
+                "stale-date": 1_700_000_600,
+                "dismissal-date": 1_700_003_600,
+            },
+        },
+        "live_activity_token": "activity-token",
+    });
+
+    assert_eq!(expected_payload, payload);
+}
+
+#[test]
+fn should_render_live_activity_start_attributes() {
+    let config = LiveActivityUpdate::start(
+        "push-to-start-token",
+        "MatchAttributes",
+        json!({ "home": "A", "away": "B" }),
+        json!({ "score": "0-0" }),
+    )
+    .build();
+
+    let aps = &config.payload.unwrap()["aps"];
+
+    assert_eq!(json!("start"), aps["event"]);
+    assert_eq!(json!("MatchAttributes"), aps["attributes-type"]);
+    assert_eq!(json!({ "home": "A", "away": "B" }), aps["attributes"]);
+}
+
+#[test]
+fn should_render_critical_alert_sound() {
+    let aps = Aps::builder().sound(Sound::critical("alarm.caf", 0.5).unwrap()).build();
+
+    let payload = serde_json::to_value(&aps).unwrap();
+
+    assert_eq!(
+        json!({ "sound": { "critical": 1, "name": "alarm.caf", "volume": 0.5 } }),
+        payload
+    );
+    assert_eq!(Err(SoundError::InvalidVolume(1.5)), Sound::critical("alarm.caf", 1.5));
+    assert!(Sound::critical("alarm.caf", f64::NAN).is_err());
+    match Sound::critical("alarm.caf", 0.5).unwrap() {
+        Sound::Critical(sound) => assert_eq!(("alarm.caf", 0.5), (sound.name(), sound.volume())),
+        sound => panic!("unexpected sound: {:?}", sound),
+    }
+}
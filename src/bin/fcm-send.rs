@@ -0,0 +1,124 @@
+// cargo run --features cli --bin fcm-send -- --help
+
+use std::path::PathBuf;
+
+use clap::{ArgGroup, Parser};
+use fcm::{
+    message::{Message, Notification, Target},
+    FcmClient,
+};
+
+/// Send a message with Firebase Cloud Messaging.
+///
+/// The message is built from the flags, or read from a JSON file in the
+/// format of the `message` field of the FCM API. Credentials are read from
+/// the file in `GOOGLE_APPLICATION_CREDENTIALS` unless `--key` is given.
+#[derive(Parser, Debug)]
+#[command(name = "fcm-send", version, group(ArgGroup::new("target").args(["token", "topic", "condition"])))]
+struct CliArgs {
+    /// Registration token of the device.
+    #[arg(long)]
+    token: Option<String>,
+    /// Topic name, without the `/topics/` prefix.
+    #[arg(long)]
+    topic: Option<String>,
+    /// Condition, for example `'news' in topics && 'sports' in topics`.
+    #[arg(long)]
+    condition: Option<String>,
+    /// Notification title.
+    #[arg(long, conflicts_with = "file")]
+    title: Option<String>,
+    /// Notification body.
+    #[arg(long, conflicts_with = "file")]
+    body: Option<String>,
+    /// Notification image URL.
+    #[arg(long, conflicts_with = "file")]
+    image: Option<String>,
+    /// Data payload entry, can be repeated.
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_data_entry, conflicts_with = "file")]
+    data: Vec<(String, String)>,
+    /// Send the message in this JSON file. A target flag overrides the
+    /// target in the file.
+    #[arg(long, value_name = "FILE")]
+    file: Option<PathBuf>,
+    /// Path to the service account key JSON file.
+    #[arg(short = 'k', long, value_name = "FILE")]
+    key: Option<PathBuf>,
+    /// Validate the message with FCM without delivering it.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl CliArgs {
+    fn target(&self) -> Option<Target> {
+        let target = if let Some(token) = &self.token {
+            Target::Token(token.clone())
+        } else if let Some(topic) = &self.topic {
+            Target::Topic(topic.clone())
+        } else {
+            Target::Condition(self.condition.clone()?)
+        };
+        Some(target)
+    }
+
+    fn message(&self) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
+        let path = match &self.file {
+            Some(path) => path,
+            None => return Ok(serde_json::to_value(self.built_message()?)?),
+        };
+        let mut message: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        if let (Some(target), Some(object)) = (self.target(), message.as_object_mut()) {
+            object.retain(|key, _| !["token", "topic", "condition"].contains(&key.as_str()));
+            let (key, value) = match target {
+                Target::Token(token) => ("token", token),
+                Target::Topic(topic) => ("topic", topic),
+                Target::Condition(condition) => ("condition", condition),
+            };
+            object.insert(key.to_string(), value.into());
+        }
+        Ok(message)
+    }
+
+    fn built_message(&self) -> Result<Message, &'static str> {
+        let target = self
+            .target()
+            .ok_or("one of --token, --topic, --condition or --file is required")?;
+        let mut builder = Message::builder(target);
+        if self.title.is_some() || self.body.is_some() || self.image.is_some() {
+            builder = builder.notification(Notification {
+                title: self.title.clone(),
+                body: self.body.clone(),
+                image: self.image.clone(),
+            });
+        }
+        for (key, value) in &self.data {
+            builder = builder.data_entry(key, value);
+        }
+        Ok(builder.build())
+    }
+}
+
+fn parse_data_entry(entry: &str) -> Result<(String, String), String> {
+    let (key, value) = entry.split_once('=').ok_or("expected KEY=VALUE")?;
+    Ok((key.to_string(), value.to_string()))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let args = CliArgs::parse();
+    let message = args.message()?;
+
+    let builder = FcmClient::builder().dry_run(args.dry_run);
+    let builder = match &args.key {
+        Some(path) => builder.service_account_key_json_path(path),
+        None => builder,
+    };
+    let client = builder.build().await?;
+
+    let response = client.send_raw(&message).await?;
+    println!("{}", serde_json::to_string_pretty(&response)?);
+    match response.error() {
+        Some(error) => Err(format!("FCM returned {:?}", error).into()),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,128 @@
+//! Synchronous client for code which does not run in an async runtime,
+//! such as CLI tools.
+//!
+//! ```no_run
+//! use fcm::message::{Message, Target};
+//!
+//! # fn run() -> Result<(), fcm::FcmClientError> {
+//! let client = fcm::blocking::FcmClient::build(
+//!     fcm::FcmClient::builder().service_account_key_json_path("service_account_key.json"),
+//! )?;
+//! let message = Message::builder(Target::Token("token".to_string())).build();
+//! let response = client.send(&message)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The methods must not be called from an async runtime, because they
+//! block the thread until the request completes.
+
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::runtime::Runtime;
+
+use crate::client::response::FcmResponse;
+use crate::message::Message;
+use crate::{BatchResponse, FcmClientBuilder, FcmClientError};
+
+/// Blocking wrapper of [crate::FcmClient] which runs it on an internal
+/// single threaded runtime.
+pub struct FcmClient {
+    client: crate::FcmClient,
+    runtime: Runtime,
+}
+
+impl FcmClient {
+    /// Build the client configured by `builder`.
+    pub fn build(builder: FcmClientBuilder) -> Result<Self, FcmClientError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(FcmClientError::Runtime)?;
+        let client = runtime.block_on(builder.build())?;
+        Ok(Self { client, runtime })
+    }
+
+    /// See [crate::FcmClient::send].
+    pub fn send(&self, message: impl AsRef<Message>) -> Result<FcmResponse, FcmClientError> {
+        self.block_on(self.client.send(message))
+    }
+
+    /// See [crate::FcmClient::send_to_project].
+    pub fn send_to_project(
+        &self,
+        project_id: &str,
+        message: impl AsRef<Message>,
+    ) -> Result<FcmResponse, FcmClientError> {
+        self.block_on(self.client.send_to_project(project_id, message))
+    }
+
+    /// See [crate::FcmClient::send_each].
+    pub fn send_each<I>(&self, messages: I) -> BatchResponse
+    where
+        I: IntoIterator,
+        I::Item: AsRef<Message>,
+    {
+        self.block_on(self.client.send_each(messages))
+    }
+
+    /// See [crate::FcmClient::validate_each].
+    pub fn validate_each<I>(&self, messages: I) -> BatchResponse
+    where
+        I: IntoIterator,
+        I::Item: AsRef<Message>,
+    {
+        self.block_on(self.client.validate_each(messages))
+    }
+
+    /// See [crate::FcmClient::close].
+    pub fn close(&self, deadline: Duration) -> Result<(), FcmClientError> {
+        self.block_on(self.client.close(deadline))
+    }
+
+    /// See [crate::FcmClient::shutdown].
+    pub fn shutdown(&self) {
+        self.block_on(self.client.shutdown())
+    }
+
+    /// Run a future on the internal runtime, for async methods of the
+    /// wrapped client which this client does not have:
+    ///
+    /// ```no_run
+    /// # fn run(client: fcm::blocking::FcmClient) -> Result<(), fcm::FcmClientError> {
+    /// let info = client.block_on(client.async_client().get_instance_info("token"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// The wrapped async client.
+    pub fn async_client(&self) -> &crate::FcmClient {
+        &self.client
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::message::Target;
+    use crate::{MockReply, MockServer};
+
+    #[test]
+    fn test_blocking_client_sends_without_caller_runtime() {
+        let server_runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = server_runtime.block_on(MockServer::start()).unwrap();
+        server.reply(MockReply::Unregistered);
+
+        let client = FcmClient::build(crate::FcmClient::builder().endpoint(server.url())).unwrap();
+        let messages: Vec<Message> = (0..3)
+            .map(|i| Message::builder(Target::Token(format!("token-{}", i))).build())
+            .collect();
+
+        assert_eq!(client.send(&messages[0]).unwrap().http_status_code(), 404);
+        assert_eq!(client.send_each(&messages).success_count(), 3);
+    }
+}
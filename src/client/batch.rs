@@ -0,0 +1,171 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::Notify;
+
+use crate::client::canary::CanaryReport;
+use crate::client::response::FcmResponse;
+use crate::client::FcmClientError;
+
+/// Default value for [crate::FcmClientBuilder::batch_concurrency].
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 10;
+
+/// Results of [crate::FcmClient::send_each] and
+/// [crate::FcmClient::validate_each] in the same order as the messages.
+#[derive(Debug)]
+pub struct BatchResponse {
+    responses: Vec<Result<FcmResponse, FcmClientError>>,
+    canary: Option<CanaryReport>,
+}
+
+impl BatchResponse {
+    pub(crate) fn new(responses: Vec<Result<FcmResponse, FcmClientError>>) -> Self {
+        Self {
+            responses,
+            canary: None,
+        }
+    }
+
+    pub(crate) fn with_canary(mut self, canary: CanaryReport) -> Self {
+        self.canary = Some(canary);
+        self
+    }
+
+    /// Result of the canary stage of [crate::FcmClient::send_each_with_canary].
+    pub fn canary(&self) -> Option<&CanaryReport> {
+        self.canary.as_ref()
+    }
+
+    pub fn responses(&self) -> &[Result<FcmResponse, FcmClientError>] {
+        &self.responses
+    }
+
+    pub fn into_responses(self) -> Vec<Result<FcmResponse, FcmClientError>> {
+        self.responses
+    }
+
+    /// Number of messages which FCM accepted.
+    pub fn success_count(&self) -> usize {
+        self.responses.iter().filter(|result| is_success(result)).count()
+    }
+
+    /// Number of messages which FCM rejected or which could not be sent,
+    /// including cancelled messages.
+    pub fn failure_count(&self) -> usize {
+        self.responses.len() - self.success_count()
+    }
+
+    /// Number of messages which were not sent or whose sending was
+    /// abandoned because the batch was cancelled.
+    pub fn cancelled_count(&self) -> usize {
+        self.responses
+            .iter()
+            .filter(|result| matches!(result, Err(FcmClientError::Cancelled)))
+            .count()
+    }
+}
+
+const RUNNING: u8 = 0;
+const CANCELLED: u8 = 1;
+const ABORTED: u8 = 2;
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    state: AtomicU8,
+    aborted: Notify,
+}
+
+/// Handle for cancelling a batch which is being sent with
+/// [crate::FcmClient::send_each_cancellable], or single messages sent with
+/// [crate::FcmClient::send_cancellable]. Clones of the handle cancel the
+/// same sends, so one handle can stop everything a service is sending when
+/// it shuts down.
+///
+/// Messages which were not sent get [FcmClientError::Cancelled] in the
+/// [BatchResponse].
+#[derive(Debug, Clone, Default)]
+pub struct BatchCancellation {
+    inner: Arc<CancellationState>,
+}
+
+impl BatchCancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop sending new messages and let messages which are currently
+    /// being sent complete.
+    pub fn cancel(&self) {
+        let _ = self
+            .inner
+            .state
+            .compare_exchange(RUNNING, CANCELLED, Ordering::SeqCst, Ordering::SeqCst);
+    }
+
+    /// Stop sending new messages and abandon messages which are currently
+    /// being sent. FCM may or may not have accepted the abandoned messages.
+    pub fn abort(&self) {
+        self.inner.state.store(ABORTED, Ordering::SeqCst);
+        self.inner.aborted.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.state.load(Ordering::SeqCst) != RUNNING
+    }
+
+    /// Wait until [BatchCancellation::abort] is called.
+    pub(crate) async fn aborted(&self) {
+        loop {
+            let aborted = self.inner.aborted.notified();
+            tokio::pin!(aborted);
+            aborted.as_mut().enable();
+            if self.inner.state.load(Ordering::SeqCst) == ABORTED {
+                return;
+            }
+            aborted.await;
+        }
+    }
+}
+
+fn is_success(result: &Result<FcmResponse, FcmClientError>) -> bool {
+    matches!(result, Ok(response) if response.error().is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_abort_wakes_waiters() {
+        let cancellation = BatchCancellation::new();
+        assert!(!cancellation.is_cancelled());
+
+        let waiter = {
+            let cancellation = cancellation.clone();
+            tokio::spawn(async move { cancellation.aborted().await })
+        };
+        cancellation.cancel();
+        assert!(cancellation.is_cancelled());
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        cancellation.abort();
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_cancelled_count() {
+        let batch = BatchResponse::new(vec![
+            Err(FcmClientError::Cancelled),
+            Err(FcmClientError::Closed),
+            Err(FcmClientError::Cancelled),
+        ]);
+
+        assert_eq!(2, batch.cancelled_count());
+        assert_eq!(3, batch.failure_count());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::client::response::{FcmResponse, FcmResponseError};
+use crate::client::FcmClientError;
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq)]
+pub enum CanaryPolicyError {
+    #[error("Canary fraction {0} is not in range 0.0 < fraction <= 1.0")]
+    InvalidFraction(f64),
+    #[error("Maximum canary error rate {0} is not in range 0.0..=1.0")]
+    InvalidErrorRate(f64),
+}
+
+/// Canary stage of [crate::FcmClient::send_each_with_canary]. A fraction of
+/// the messages is sent first, and the rest are sent only if the error rate
+/// of the canary messages is at most `max_error_rate`.
+///
+/// Unregistered tokens are expected in every large audience, so messages
+/// which fail with [FcmResponseError::Unregistered] do not count as errors.
+///
+/// ```rust
+/// use fcm::CanaryPolicy;
+///
+/// // Send 1% first, at least 100 messages, and stop if over 5% fail.
+/// let canary = CanaryPolicy::new(0.01, 0.05).unwrap().min_messages(100);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanaryPolicy {
+    fraction: f64,
+    max_error_rate: f64,
+    min_messages: usize,
+}
+
+impl CanaryPolicy {
+    pub fn new(fraction: f64, max_error_rate: f64) -> Result<Self, CanaryPolicyError> {
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            return Err(CanaryPolicyError::InvalidFraction(fraction));
+        }
+        if !(0.0..=1.0).contains(&max_error_rate) {
+            return Err(CanaryPolicyError::InvalidErrorRate(max_error_rate));
+        }
+        Ok(Self {
+            fraction,
+            max_error_rate,
+            min_messages: 1,
+        })
+    }
+
+    /// Minimum number of canary messages. Default is 1.
+    pub fn min_messages(mut self, min_messages: usize) -> Self {
+        self.min_messages = min_messages;
+        self
+    }
+
+    /// Number of canary messages in a batch of `total` messages.
+    pub(crate) fn canary_size(&self, total: usize) -> usize {
+        let size = (total as f64 * self.fraction).ceil() as usize;
+        size.max(self.min_messages).min(total)
+    }
+
+    pub(crate) fn evaluate(&self, responses: &[Result<FcmResponse, FcmClientError>]) -> CanaryReport {
+        let errors = responses.iter().filter(|result| is_canary_error(result)).count();
+        let error_rate = if responses.is_empty() {
+            0.0
+        } else {
+            errors as f64 / responses.len() as f64
+        };
+        CanaryReport {
+            size: responses.len(),
+            error_rate,
+            passed: error_rate <= self.max_error_rate,
+        }
+    }
+}
+
+/// Result of the canary stage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanaryReport {
+    /// Number of canary messages.
+    pub size: usize,
+    /// Fraction of canary messages which failed, not counting unregistered
+    /// tokens.
+    pub error_rate: f64,
+    /// `false` if the rest of the messages were not sent.
+    pub passed: bool,
+}
+
+fn is_canary_error(result: &Result<FcmResponse, FcmClientError>) -> bool {
+    match result {
+        Ok(response) => !matches!(response.error(), None | Some(FcmResponseError::Unregistered)),
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(http_status_code: u16) -> Result<FcmResponse, FcmClientError> {
+        let mut json = serde_json::Map::new();
+        json.insert("name".to_string(), "projects/p/messages/1".into());
+        Ok(FcmResponse::new(http_status_code, json, None))
+    }
+
+    #[test]
+    fn test_canary_size() {
+        let canary = CanaryPolicy::new(0.01, 0.1).unwrap();
+        assert_eq!(canary.canary_size(1_000_000), 10_000);
+        assert_eq!(canary.canary_size(10), 1);
+        assert_eq!(canary.min_messages(100).canary_size(10), 10);
+        assert!(CanaryPolicy::new(0.0, 0.1).is_err());
+        assert!(CanaryPolicy::new(0.5, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_canary_ignores_unregistered_tokens() {
+        let canary = CanaryPolicy::new(0.1, 0.25).unwrap();
+
+        let report = canary.evaluate(&[response(200), response(404), response(404), response(400)]);
+        assert_eq!(report.error_rate, 0.25);
+        assert!(report.passed);
+
+        let report = canary.evaluate(&[response(200), response(400), Err(FcmClientError::Closed)]);
+        assert!(!report.passed);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[tokio::test]
+    async fn test_failed_canary_cancels_the_rest() {
+        use crate::message::{Message, Target};
+        use crate::{FcmClient, MockReply, MockServer};
+
+        let server = MockServer::start().await.unwrap();
+        server.replies([MockReply::Internal, MockReply::Success]);
+        let client = FcmClient::builder().endpoint(server.url()).build().await.unwrap();
+        let messages: Vec<Message> = (0..10)
+            .map(|i| Message::builder(Target::Token(format!("token-{}", i))).build())
+            .collect();
+
+        let batch = client
+            .send_each_with_canary(messages, &CanaryPolicy::new(0.2, 0.1).unwrap())
+            .await;
+
+        assert!(!batch.canary().unwrap().passed);
+        assert_eq!(batch.cancelled_count(), 8);
+        assert_eq!(server.requests().len(), 2);
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// Connection pool and keepalive settings of the HTTP client, set with
+/// [crate::FcmClientBuilder::pool_max_idle_per_host] and the related
+/// builder methods. Defaults are those of `reqwest`.
+///
+/// The fetch API manages connections itself, so these are ignored on
+/// wasm32.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConnectionConfig {
+    /// Maximum idle connections kept per host. `None` is unlimited.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long idle connections are kept. `None` keeps them forever.
+    #[serde(serialize_with = "crate::duration::serialize_option")]
+    pub pool_idle_timeout: Option<Duration>,
+    /// Interval of TCP keepalive probes. `None` disables them.
+    #[serde(serialize_with = "crate::duration::serialize_option")]
+    pub tcp_keepalive: Option<Duration>,
+    /// Interval of HTTP/2 keepalive pings. `None` disables them.
+    #[serde(serialize_with = "crate::duration::serialize_option")]
+    pub http2_keep_alive_interval: Option<Duration>,
+    /// How long to wait for a ping acknowledgement before closing the
+    /// connection. `None` uses the default of `hyper`, 20 seconds.
+    #[serde(serialize_with = "crate::duration::serialize_option")]
+    pub http2_keep_alive_timeout: Option<Duration>,
+    /// Send pings also when there are no requests in progress.
+    pub http2_keep_alive_while_idle: bool,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            tcp_keepalive: None,
+            http2_keep_alive_interval: None,
+            http2_keep_alive_timeout: None,
+            http2_keep_alive_while_idle: false,
+        }
+    }
+}
+
+impl ConnectionConfig {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn apply(&self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        let builder = builder
+            .pool_max_idle_per_host(self.pool_max_idle_per_host.unwrap_or(usize::MAX))
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(self.tcp_keepalive)
+            .http2_keep_alive_interval(self.http2_keep_alive_interval)
+            .http2_keep_alive_while_idle(self.http2_keep_alive_while_idle);
+        match self.http2_keep_alive_timeout {
+            Some(timeout) => builder.http2_keep_alive_timeout(timeout),
+            None => builder,
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Device group management with the `notification_key` API.
+//!
+//! <https://firebase.google.com/docs/cloud-messaging/android/topic-messaging#managing_device_groups>
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{FcmClient, FcmClientError};
+
+const DEVICE_GROUP_URL: &str = "https://fcm.googleapis.com/fcm/notification";
+
+#[derive(thiserror::Error, Debug)]
+pub enum DeviceGroupError {
+    #[error("Sender ID is required for device group management")]
+    SenderIdIsMissing,
+    #[error("At least one registration token is required")]
+    NoTokens,
+}
+
+#[derive(Deserialize)]
+struct NotificationKeyResponse {
+    notification_key: String,
+}
+
+fn operation_body(
+    operation: &str,
+    notification_key_name: &str,
+    notification_key: Option<&str>,
+    tokens: &[impl AsRef<str>],
+) -> Value {
+    let mut body = json!({
+        "operation": operation,
+        "notification_key_name": notification_key_name,
+        "registration_ids": tokens.iter().map(AsRef::as_ref).collect::<Vec<_>>(),
+    });
+    if let Some(notification_key) = notification_key {
+        body["notification_key"] = notification_key.into();
+    }
+    body
+}
+
+impl FcmClient {
+    /// Create a device group named `notification_key_name` with
+    /// `tokens` as members. Returns the notification key of the group,
+    /// which can be used as a target with [crate::message::Target::device_group].
+    pub async fn create_device_group(
+        &self,
+        notification_key_name: &str,
+        tokens: &[impl AsRef<str>],
+    ) -> Result<String, FcmClientError> {
+        self.device_group_operation("create", notification_key_name, None, tokens)
+            .await
+    }
+
+    /// Add `tokens` to a device group. Returns the notification key of
+    /// the group.
+    pub async fn add_to_device_group(
+        &self,
+        notification_key_name: &str,
+        notification_key: &str,
+        tokens: &[impl AsRef<str>],
+    ) -> Result<String, FcmClientError> {
+        self.device_group_operation("add", notification_key_name, Some(notification_key), tokens)
+            .await
+    }
+
+    /// Remove `tokens` from a device group. The group is deleted when its
+    /// last member is removed. Returns the notification key of the group.
+    pub async fn remove_from_device_group(
+        &self,
+        notification_key_name: &str,
+        notification_key: &str,
+        tokens: &[impl AsRef<str>],
+    ) -> Result<String, FcmClientError> {
+        self.device_group_operation("remove", notification_key_name, Some(notification_key), tokens)
+            .await
+    }
+
+    /// Get the notification key of the device group named
+    /// `notification_key_name`.
+    pub async fn get_device_group_key(&self, notification_key_name: &str) -> Result<String, FcmClientError> {
+        let request = self
+            .http_client
+            .get(DEVICE_GROUP_URL)
+            .header("project_id", self.sender_id()?)
+            .query(&[("notification_key_name", notification_key_name)]);
+        let response = self.management_request(request).await?;
+        let response: NotificationKeyResponse = serde_json::from_slice(&response.body)?;
+        Ok(response.notification_key)
+    }
+
+    async fn device_group_operation(
+        &self,
+        operation: &str,
+        notification_key_name: &str,
+        notification_key: Option<&str>,
+        tokens: &[impl AsRef<str>],
+    ) -> Result<String, FcmClientError> {
+        if tokens.is_empty() {
+            return Err(DeviceGroupError::NoTokens.into());
+        }
+        let body = operation_body(operation, notification_key_name, notification_key, tokens);
+        let request = self
+            .http_client
+            .post(DEVICE_GROUP_URL)
+            .header("project_id", self.sender_id()?)
+            .json(&body);
+        let response = self.management_request(request).await?;
+        let response: NotificationKeyResponse = serde_json::from_slice(&response.body)?;
+        Ok(response.notification_key)
+    }
+
+    fn sender_id(&self) -> Result<&str, FcmClientError> {
+        self.sender_id
+            .as_deref()
+            .ok_or_else(|| DeviceGroupError::SenderIdIsMissing.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_body() {
+        assert_eq!(
+            json!({
+                "operation": "create",
+                "notification_key_name": "user-1",
+                "registration_ids": ["a", "b"],
+            }),
+            operation_body("create", "user-1", None, &["a", "b"])
+        );
+        assert_eq!(
+            json!({
+                "operation": "remove",
+                "notification_key_name": "user-1",
+                "registration_ids": ["a"],
+                "notification_key": "key",
+            }),
+            operation_body("remove", "user-1", Some("key"), &["a"])
+        );
+    }
+}
@@ -0,0 +1,131 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::net::SocketAddr;
+
+use serde::Serialize;
+
+/// IP version used when connecting to FCM.
+///
+/// Useful in environments with broken dual-stack routing, where trying the
+/// unreachable address family first causes long connection stalls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IpVersion {
+    /// Use the addresses in the order returned by the system resolver.
+    #[default]
+    Any,
+    /// Connect only using IPv4 addresses.
+    V4Only,
+    /// Connect only using IPv6 addresses.
+    V6Only,
+    /// Try IPv4 addresses first and fall back to IPv6.
+    PreferV4,
+    /// Try IPv6 addresses first and fall back to IPv4.
+    PreferV6,
+}
+
+impl IpVersion {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn apply(self, mut addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+        match self {
+            IpVersion::Any => (),
+            IpVersion::V4Only => addrs.retain(SocketAddr::is_ipv4),
+            IpVersion::V6Only => addrs.retain(SocketAddr::is_ipv6),
+            // Stable sort keeps the resolver order within the same family.
+            IpVersion::PreferV4 => addrs.sort_by_key(SocketAddr::is_ipv6),
+            IpVersion::PreferV6 => addrs.sort_by_key(SocketAddr::is_ipv4),
+        }
+        addrs
+    }
+}
+
+/// Resolvers for the native HTTP client. On wasm32 names are resolved by
+/// the host.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod resolver {
+    use std::io;
+
+    use reqwest::dns::{Addrs, Resolve, Resolving};
+    use yup_oauth2::hyper::client::connect::dns::Name;
+
+    use super::IpVersion;
+    use crate::client::rt;
+
+    /// DNS resolver which filters and orders the resolved addresses by
+    /// [IpVersion].
+    pub(crate) struct IpVersionResolver {
+        pub ip_version: IpVersion,
+    }
+
+    impl Resolve for IpVersionResolver {
+        fn resolve(&self, name: Name) -> Resolving {
+            let ip_version = self.ip_version;
+            Box::pin(async move {
+                let addrs = rt::lookup_host(name.as_str()).await?;
+                let addrs = ip_version.apply(addrs);
+                if addrs.is_empty() {
+                    let message = format!("{} has no addresses matching {:?}", name.as_str(), ip_version);
+                    return Err(io::Error::new(io::ErrorKind::AddrNotAvailable, message).into());
+                }
+                Ok(Box::new(addrs.into_iter()) as Addrs)
+            })
+        }
+    }
+
+    /// DNS resolver which fails every lookup, so that no connections can be
+    /// made to hosts by name.
+    pub(crate) struct ForbiddenResolver;
+
+    impl Resolve for ForbiddenResolver {
+        fn resolve(&self, name: Name) -> Resolving {
+            let message = format!("network access is forbidden, refusing to resolve {}", name.as_str());
+            Box::pin(async move { Err(io::Error::new(io::ErrorKind::PermissionDenied, message).into()) })
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::resolver::ForbiddenResolver;
+    use super::*;
+
+    fn addrs() -> Vec<SocketAddr> {
+        vec![
+            "[2001:db8::1]:0".parse().unwrap(),
+            "192.0.2.1:0".parse().unwrap(),
+            "[2001:db8::2]:0".parse().unwrap(),
+            "192.0.2.2:0".parse().unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_ip_version_filters_addresses() {
+        let v4: Vec<SocketAddr> = vec!["192.0.2.1:0".parse().unwrap(), "192.0.2.2:0".parse().unwrap()];
+        assert_eq!(v4, IpVersion::V4Only.apply(addrs()));
+        assert!(IpVersion::V6Only.apply(addrs()).iter().all(SocketAddr::is_ipv6));
+        assert_eq!(addrs(), IpVersion::Any.apply(addrs()));
+    }
+
+    #[test]
+    fn test_ip_version_orders_addresses() {
+        let preferred_v4 = IpVersion::PreferV4.apply(addrs());
+        assert_eq!(addrs()[1], preferred_v4[0]);
+        assert_eq!(addrs()[3], preferred_v4[1]);
+        assert_eq!(4, preferred_v4.len());
+
+        let preferred_v6 = IpVersion::PreferV6.apply(addrs());
+        assert_eq!(addrs()[0], preferred_v6[0]);
+        assert_eq!(addrs()[2], preferred_v6[1]);
+    }
+
+    #[tokio::test]
+    async fn test_forbidden_resolver_fails_requests() {
+        let client = reqwest::Client::builder()
+            .dns_resolver(std::sync::Arc::new(ForbiddenResolver))
+            .build()
+            .unwrap();
+
+        let error = client.get("https://fcm.googleapis.com/").send().await.unwrap_err();
+
+        assert!(error.is_connect());
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::client::{
+    AdaptiveThrottling, ConnectionConfig, DeliveryProfile, FcmClientBuilder, IpVersion, DEFAULT_BATCH_CONCURRENCY,
+};
+use crate::client::{TelemetryConfig, DEFAULT_MAX_RESPONSE_BODY_SIZE, FCM_BASE_URL};
+
+/// Where the client loaded its service account key from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "kind")]
+pub enum CredentialSource {
+    /// Key file, set with the builder or `GOOGLE_APPLICATION_CREDENTIALS`.
+    KeyFile { path: PathBuf, reload_on_change: bool },
+    /// Key JSON or `ServiceAccountKey` set with the builder.
+    KeyJson,
+    /// Authenticator of the application set with the builder.
+    Authenticator,
+    /// Access token obtained by the host.
+    AccessToken,
+    /// No credentials are needed, for example when replaying fixtures.
+    None,
+}
+
+/// Snapshot of the settings of a [crate::FcmClient] after defaults are
+/// applied, returned by [crate::FcmClient::effective_config]. It contains no
+/// secrets, so it can be logged at startup or served from a debug endpoint.
+/// Durations serialize like `"10s"`. Callbacks, such as interceptors and
+/// observers, are shown only as their number or whether they are set.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectiveConfig {
+    pub endpoint: String,
+    pub project_id: Option<String>,
+    pub sender_id: Option<String>,
+    pub credentials: CredentialSource,
+    pub token_cache_json_path: Option<PathBuf>,
+    #[serde(serialize_with = "crate::duration::serialize_option")]
+    pub request_timeout: Option<Duration>,
+    pub dry_run: bool,
+    pub max_retries: u32,
+    /// Set with [crate::FcmClientBuilder::on_attempt].
+    pub on_attempt: bool,
+    #[serde(serialize_with = "crate::duration::serialize_option")]
+    pub max_retry_wait_time: Option<Duration>,
+    pub max_response_body_size: usize,
+    pub ip_version: IpVersion,
+    pub connection: ConnectionConfig,
+    /// Number of root certificates added with
+    /// [crate::FcmClientBuilder::add_root_certificate].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub root_certificates: usize,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub accept_invalid_certs: bool,
+    pub batch_concurrency: usize,
+    /// Limit of concurrent send attempts. There is no rate limit.
+    pub adaptive_throttling: Option<AdaptiveThrottling>,
+    pub interceptors: usize,
+    pub telemetry: Option<TelemetryConfig>,
+    /// Set with [crate::FcmClientBuilder::metrics_observer].
+    pub metrics_observer: bool,
+    pub schema_version: Option<(String, u32)>,
+    pub profiles: BTreeMap<String, DeliveryProfile>,
+    pub default_profile: Option<String>,
+    pub token_hints: bool,
+    pub forbid_network: bool,
+}
+
+impl EffectiveConfig {
+    /// Settings of `builder`, with credentials set to
+    /// [CredentialSource::None] until they are loaded.
+    pub(crate) fn new(builder: &FcmClientBuilder) -> Self {
+        let retry_policy = builder.retry_policy.clone().unwrap_or_default();
+        Self {
+            endpoint: builder.endpoint.as_deref().unwrap_or(FCM_BASE_URL).to_string(),
+            project_id: None,
+            sender_id: builder.sender_id.clone(),
+            credentials: CredentialSource::None,
+            token_cache_json_path: builder.token_cache_json_path.clone(),
+            request_timeout: builder.fcm_request_timeout,
+            dry_run: builder.dry_run.unwrap_or(false),
+            max_retries: retry_policy.max_retries(),
+            on_attempt: builder.on_attempt.is_some(),
+            max_retry_wait_time: (retry_policy.max_retries() > 0).then_some(retry_policy.max_wait_time),
+            max_response_body_size: builder.max_response_body_size.unwrap_or(DEFAULT_MAX_RESPONSE_BODY_SIZE),
+            ip_version: builder.ip_version.unwrap_or_default(),
+            connection: builder.connection.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            root_certificates: builder.tls.root_certificates.len(),
+            #[cfg(not(target_arch = "wasm32"))]
+            accept_invalid_certs: builder.tls.accept_invalid_certs,
+            batch_concurrency: builder.batch_concurrency.unwrap_or(DEFAULT_BATCH_CONCURRENCY),
+            adaptive_throttling: builder.adaptive_throttling.clone(),
+            interceptors: builder.interceptors.0.len(),
+            telemetry: builder.telemetry.as_ref().map(|telemetry| telemetry.config().clone()),
+            metrics_observer: builder.metrics_observer.is_some(),
+            schema_version: builder.schema_version.clone(),
+            profiles: builder
+                .profiles
+                .iter()
+                .map(|(name, profile)| (name.clone(), profile.clone()))
+                .collect(),
+            default_profile: builder.default_profile.clone(),
+            token_hints: builder.token_hints.is_some(),
+            forbid_network: builder.forbid_network,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RetryPolicy;
+
+    #[test]
+    fn test_effective_config_applies_defaults_and_hides_key() {
+        let builder = FcmClientBuilder::new()
+            .service_account_key_json_string("{\"private_key\": \"secret\"}")
+            .fcm_request_timeout(Duration::from_secs(10))
+            .retry_policy(RetryPolicy::new(3))
+            .profile("marketing", DeliveryProfile::new().ttl(Duration::from_secs(3600)))
+            .http2_keep_alive_interval(Duration::from_secs(30))
+            .add_root_certificate(crate::RootCertificate::from_der(vec![1, 2, 3]))
+            .danger_accept_invalid_certs(true)
+            .adaptive_throttling(crate::AdaptiveThrottling::new(8))
+            .telemetry(
+                crate::TelemetryConfig::new().mode(crate::TelemetryMode::CountersOnly),
+                |_| {},
+            );
+
+        let json = serde_json::to_value(EffectiveConfig::new(&builder)).unwrap();
+
+        assert_eq!(json["endpoint"], "https://fcm.googleapis.com");
+        assert_eq!(json["request_timeout"], "10s");
+        assert_eq!(json["max_retries"], 3);
+        assert_eq!(json["max_retry_wait_time"], "600s");
+        assert_eq!(json["max_response_body_size"], DEFAULT_MAX_RESPONSE_BODY_SIZE);
+        assert_eq!(json["ip_version"], "any");
+        assert_eq!(json["connection"]["pool_idle_timeout"], "90s");
+        assert_eq!(json["connection"]["http2_keep_alive_interval"], "30s");
+        assert_eq!(json["connection"]["tcp_keepalive"], serde_json::Value::Null);
+        assert_eq!(json["profiles"]["marketing"]["ttl"], "3600s");
+        assert_eq!(json["root_certificates"], 1);
+        assert_eq!(json["accept_invalid_certs"], true);
+        assert_eq!(json["adaptive_throttling"]["max_concurrency"], 8);
+        assert_eq!(json["adaptive_throttling"]["quiet_period"], "10s");
+        assert_eq!(json["telemetry"]["flush_interval"], "10s");
+        assert_eq!(json["telemetry"]["mode"], "counters-only");
+        assert_eq!(json["interceptors"], 0);
+        assert_eq!(json["metrics_observer"], false);
+        assert!(!json.to_string().contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn test_access_token_from_host_is_not_exposed() {
+        let client = crate::FcmClient::builder()
+            .access_token("my-project", "ya29.secret")
+            .build()
+            .await
+            .unwrap();
+        client.set_access_token("ya29.rotated").unwrap();
+
+        let config = client.effective_config();
+
+        assert_eq!(config.credentials, CredentialSource::AccessToken);
+        assert_eq!(config.project_id.as_deref(), Some("my-project"));
+        assert!(!serde_json::to_string(&config).unwrap().contains("ya29"));
+    }
+}
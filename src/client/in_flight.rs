@@ -0,0 +1,125 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+use crate::client::rt;
+
+/// Tracks requests which are currently in progress so that the client
+/// can be closed gracefully.
+#[derive(Debug, Default)]
+pub(crate) struct InFlight {
+    closed: AtomicBool,
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+impl InFlight {
+    /// Register a new request. Returns `None` if the client is closed.
+    ///
+    /// The request is unregistered when the returned guard is dropped,
+    /// which also happens when the request future is dropped before
+    /// it completes.
+    pub fn start(&self) -> Option<InFlightGuard<'_>> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlightGuard(self);
+        if self.closed.load(Ordering::SeqCst) {
+            return None;
+        }
+        Some(guard)
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// Stop accepting new requests and wait until the in-flight requests
+    /// complete. Returns the number of requests still in progress if
+    /// `deadline` elapses first.
+    pub async fn close(&self, deadline: Duration) -> Result<(), usize> {
+        rt::timeout(deadline, self.shutdown()).await.map_err(|_| self.count())
+    }
+
+    /// Stop accepting new requests and wait until the in-flight requests
+    /// complete, however long they take.
+    pub async fn shutdown(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.wait_idle().await
+    }
+
+    async fn wait_idle(&self) {
+        loop {
+            let idle = self.idle.notified();
+            tokio::pin!(idle);
+            idle.as_mut().enable();
+            if self.count() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+pub(crate) struct InFlightGuard<'a>(&'a InFlight);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        if self.0.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_close_waits_for_in_flight_requests() {
+        let in_flight = InFlight::default();
+        let guard = in_flight.start().unwrap();
+
+        let close = in_flight.close(Duration::from_secs(10));
+        let release = async {
+            tokio::task::yield_now().await;
+            drop(guard);
+        };
+        let (result, _) = tokio::join!(close, release);
+
+        assert_eq!(Ok(()), result);
+        assert_eq!(0, in_flight.count());
+    }
+
+    #[tokio::test]
+    async fn test_close_reports_in_flight_requests_after_deadline() {
+        let in_flight = InFlight::default();
+        let _guard = in_flight.start().unwrap();
+
+        assert_eq!(Err(1), in_flight.close(Duration::from_millis(10)).await);
+    }
+
+    #[tokio::test]
+    async fn test_close_after_deadline_still_rejects_new_requests() {
+        let in_flight = InFlight::default();
+        let guard = in_flight.start().unwrap();
+
+        assert_eq!(Err(1), in_flight.close(Duration::ZERO).await);
+        assert!(in_flight.start().is_none());
+        drop(guard);
+        in_flight.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_closed_client_rejects_new_requests() {
+        let in_flight = InFlight::default();
+        in_flight.close(Duration::ZERO).await.unwrap();
+
+        assert!(in_flight.is_closed());
+        assert!(in_flight.start().is_none());
+        assert_eq!(0, in_flight.count());
+    }
+}
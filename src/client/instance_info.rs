@@ -0,0 +1,119 @@
+//! App instance information from the Instance ID API.
+//!
+//! <https://developers.google.com/instance-id/reference/server#get_information_about_app_instances>
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::{FcmClient, FcmClientError};
+
+const IID_INFO_URL: &str = "https://iid.googleapis.com/iid/info";
+
+/// Topic which an app instance is subscribed to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicSubscription {
+    pub name: String,
+    /// Date of the subscription, for example `"2015-07-30"`.
+    pub add_date: Option<String>,
+}
+
+/// Information about the app instance of a registration token.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceInfo {
+    /// Package name or bundle ID of the app.
+    pub application: Option<String>,
+    pub application_version: Option<String>,
+    /// Sender ID which is authorized to send messages to the token.
+    pub authorized_entity: Option<String>,
+    /// `ANDROID`, `IOS` or `CHROME`.
+    pub platform: Option<String>,
+    pub app_signer: Option<String>,
+    pub attest_status: Option<String>,
+    pub connection_type: Option<String>,
+    pub connect_date: Option<String>,
+    #[serde(default, rename = "rel", deserialize_with = "deserialize_topics")]
+    pub topics: Vec<TopicSubscription>,
+}
+
+fn deserialize_topics<'de, D>(deserializer: D) -> Result<Vec<TopicSubscription>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Relations {
+        #[serde(default)]
+        topics: BTreeMap<String, Topic>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Topic {
+        add_date: Option<String>,
+    }
+
+    let relations = Relations::deserialize(deserializer)?;
+    Ok(relations
+        .topics
+        .into_iter()
+        .map(|(name, topic)| TopicSubscription {
+            name,
+            add_date: topic.add_date,
+        })
+        .collect())
+}
+
+impl FcmClient {
+    /// Get information about the app instance of `token`, including the
+    /// topics which it is subscribed to.
+    pub async fn get_instance_info(&self, token: &str) -> Result<InstanceInfo, FcmClientError> {
+        let url = format!("{}/{}", IID_INFO_URL, token);
+        let request = self.http_client.get(&url).query(&[("details", "true")]);
+        let response = self.management_request(request).await?;
+        Ok(serde_json::from_slice(&response.body)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_instance_info() {
+        let body = r#"{
+            "application": "com.example.app",
+            "authorizedEntity": "123456782354",
+            "platform": "ANDROID",
+            "attestStatus": "ROOTED",
+            "appSigner": "1a2bc3d4e5",
+            "connectionType": "WIFI",
+            "connectDate": "2015-05-12",
+            "rel": {
+                "topics": {
+                    "news": { "addDate": "2015-07-30" },
+                    "sports": { "addDate": "2015-07-31" }
+                }
+            }
+        }"#;
+
+        let info: InstanceInfo = serde_json::from_str(body).unwrap();
+
+        assert_eq!(Some("ANDROID"), info.platform.as_deref());
+        assert_eq!(Some("com.example.app"), info.application.as_deref());
+        assert_eq!(
+            vec![
+                TopicSubscription {
+                    name: "news".to_string(),
+                    add_date: Some("2015-07-30".to_string()),
+                },
+                TopicSubscription {
+                    name: "sports".to_string(),
+                    add_date: Some("2015-07-31".to_string()),
+                },
+            ],
+            info.topics
+        );
+        let info: InstanceInfo = serde_json::from_str(r#"{"platform":"IOS"}"#).unwrap();
+        assert!(info.topics.is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// Error returned by [Interceptor::before_send] to fail a request without
+/// sending it.
+pub type InterceptorError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Response passed to [Interceptor::after_response].
+#[derive(Debug)]
+pub struct InterceptedResponse<'a> {
+    pub url: &'a reqwest::Url,
+    pub http_status_code: u16,
+    pub body: &'a [u8],
+}
+
+/// Hooks which are called for every HTTP request to FCM, including
+/// retries and Instance ID and device group requests, for example to add
+/// headers, log requests for auditing or inject failures in tests. Set
+/// with [crate::FcmClientBuilder::interceptor].
+///
+/// ```rust
+/// use fcm::{Interceptor, InterceptorError};
+///
+/// struct TraceHeader;
+///
+/// impl Interceptor for TraceHeader {
+///     fn before_send(&self, request: &mut reqwest::Request) -> Result<(), InterceptorError> {
+///         request.headers_mut().insert("x-request-source", "billing".parse()?);
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait Interceptor: Send + Sync {
+    /// Called before the request is sent. Returning an error fails the
+    /// request with [crate::FcmClientError::Interceptor].
+    fn before_send(&self, _request: &mut reqwest::Request) -> Result<(), InterceptorError> {
+        Ok(())
+    }
+
+    /// Called after the response body is read.
+    fn after_response(&self, _response: &InterceptedResponse<'_>) {}
+}
+
+impl<T: Interceptor + ?Sized> Interceptor for Arc<T> {
+    fn before_send(&self, request: &mut reqwest::Request) -> Result<(), InterceptorError> {
+        (**self).before_send(request)
+    }
+
+    fn after_response(&self, response: &InterceptedResponse<'_>) {
+        (**self).after_response(response)
+    }
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Interceptors(pub Vec<Arc<dyn Interceptor>>);
+
+impl fmt::Debug for Interceptors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Interceptors({})", self.0.len())
+    }
+}
+
+impl Interceptors {
+    pub fn before_send(&self, request: &mut reqwest::Request) -> Result<(), InterceptorError> {
+        self.0
+            .iter()
+            .try_for_each(|interceptor| interceptor.before_send(request))
+    }
+
+    pub fn after_response(&self, response: &InterceptedResponse<'_>) {
+        for interceptor in &self.0 {
+            interceptor.after_response(response);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::message::{Message, Target};
+    use crate::{FcmClient, FcmClientError, MockReply, MockServer};
+
+    #[derive(Default)]
+    struct Chaos {
+        statuses: Mutex<Vec<u16>>,
+    }
+
+    impl Interceptor for Chaos {
+        fn before_send(&self, request: &mut reqwest::Request) -> Result<(), InterceptorError> {
+            if request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .unwrap_or_default()
+                .windows(4)
+                .any(|w| w == b"fail")
+            {
+                return Err("injected failure".into());
+            }
+            Ok(())
+        }
+
+        fn after_response(&self, response: &InterceptedResponse<'_>) {
+            self.statuses.lock().unwrap().push(response.http_status_code);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_sees_requests_and_responses() {
+        let server = MockServer::start().await.unwrap();
+        server.reply(MockReply::Unregistered);
+        let chaos = Arc::new(Chaos::default());
+        let client = FcmClient::builder()
+            .endpoint(server.url())
+            .interceptor(chaos.clone())
+            .build()
+            .await
+            .unwrap();
+
+        let message = Message::builder(Target::Token("token".to_string())).build();
+        client.send(&message).await.unwrap();
+        let message = Message::builder(Target::Token("fail".to_string())).build();
+        assert!(matches!(
+            client.send(&message).await,
+            Err(FcmClientError::Interceptor(_))
+        ));
+
+        assert_eq!(*chaos.statuses.lock().unwrap(), vec![404]);
+        assert_eq!(server.requests().len(), 1);
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration as TimeDelta, FixedOffset, NaiveTime, Utc};
+
+use crate::client::batch::BatchResponse;
+use crate::message::Message;
+use crate::FcmClient;
+
+/// Campaign which is delivered at the same local time in every timezone,
+/// for example at 9:00 for every recipient.
+///
+/// Messages are grouped by the UTC offset of the recipient, and every
+/// group is sent at the next time the delivery time is reached in that
+/// offset. Use [LocalTimeSchedule::buckets] to send the groups with an
+/// external scheduler, or [FcmClient::send_at_local_time] to wait and send
+/// them with the client.
+///
+/// ```rust
+/// use chrono::{FixedOffset, NaiveTime};
+/// use fcm::message::{Message, Target};
+/// use fcm::LocalTimeSchedule;
+///
+/// let mut schedule = LocalTimeSchedule::new(NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+/// schedule.add(
+///     Message::builder(Target::Token("helsinki".to_string())).build(),
+///     FixedOffset::east_opt(3 * 3600).unwrap(),
+/// );
+/// schedule.add(
+///     Message::builder(Target::Token("new-york".to_string())).build(),
+///     FixedOffset::west_opt(4 * 3600).unwrap(),
+/// );
+/// ```
+#[derive(Debug)]
+pub struct LocalTimeSchedule<M> {
+    delivery_time: NaiveTime,
+    buckets: BTreeMap<i32, Vec<M>>,
+}
+
+/// Messages of recipients in one UTC offset.
+#[derive(Debug)]
+pub struct ScheduledBucket<M> {
+    pub utc_offset: FixedOffset,
+    /// Next time the delivery time is reached in `utc_offset`.
+    pub send_at: DateTime<Utc>,
+    pub messages: Vec<M>,
+}
+
+impl<M> LocalTimeSchedule<M> {
+    pub fn new(delivery_time: NaiveTime) -> Self {
+        Self {
+            delivery_time,
+            buckets: BTreeMap::new(),
+        }
+    }
+
+    /// Add a message for a recipient whose local time is `utc_offset`.
+    pub fn add(&mut self, message: M, utc_offset: FixedOffset) {
+        self.buckets
+            .entry(utc_offset.local_minus_utc())
+            .or_default()
+            .push(message);
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Groups ordered by send time. A group whose delivery time has
+    /// already passed on the local day of `now` is sent on the next day.
+    pub fn buckets(self, now: DateTime<Utc>) -> Vec<ScheduledBucket<M>> {
+        let delivery_time = self.delivery_time;
+        let mut buckets: Vec<ScheduledBucket<M>> = self
+            .buckets
+            .into_iter()
+            .map(|(offset_seconds, messages)| {
+                let utc_offset = FixedOffset::east_opt(offset_seconds).expect("offset comes from a FixedOffset");
+                ScheduledBucket {
+                    utc_offset,
+                    send_at: next_local_time(now, utc_offset, delivery_time),
+                    messages,
+                }
+            })
+            .collect();
+        buckets.sort_by_key(|bucket| bucket.send_at);
+        buckets
+    }
+}
+
+/// First instant at or after `now` when the local time in `utc_offset` is
+/// `delivery_time`.
+fn next_local_time(now: DateTime<Utc>, utc_offset: FixedOffset, delivery_time: NaiveTime) -> DateTime<Utc> {
+    let local_now = now.with_timezone(&utc_offset);
+    let today =
+        local_now.date_naive().and_time(delivery_time) - TimeDelta::seconds(utc_offset.local_minus_utc().into());
+    let today = today.and_utc();
+    if today >= now {
+        today
+    } else {
+        today + TimeDelta::days(1)
+    }
+}
+
+impl FcmClient {
+    /// Send the messages of `schedule` at the delivery time of every UTC
+    /// offset, waiting between the groups. Returns the results of the
+    /// groups in the order they were sent.
+    ///
+    /// The future completes after the last group is sent, which can be up
+    /// to a day later, so it should run in its own task.
+    pub async fn send_at_local_time<M>(&self, schedule: LocalTimeSchedule<M>) -> Vec<(FixedOffset, BatchResponse)>
+    where
+        M: AsRef<Message>,
+    {
+        let mut results = Vec::new();
+        for bucket in schedule.buckets(Utc::now()) {
+            if let Ok(wait_time) = (bucket.send_at - Utc::now()).to_std() {
+                crate::client::rt::sleep(wait_time).await;
+            }
+            results.push((bucket.utc_offset, self.send_each(bucket.messages).await));
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets_are_sent_at_local_delivery_time() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T10:00:00Z").unwrap().to_utc();
+        let mut schedule = LocalTimeSchedule::new(NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        let helsinki = FixedOffset::east_opt(3 * 3600).unwrap();
+        let new_york = FixedOffset::west_opt(4 * 3600).unwrap();
+        schedule.add("fi-1", helsinki);
+        schedule.add("us-1", new_york);
+        schedule.add("fi-2", helsinki);
+        assert_eq!(schedule.len(), 3);
+
+        let buckets = schedule.buckets(now);
+
+        // 9:00 in New York is 13:00 UTC today, 9:00 in Helsinki already
+        // passed and is 6:00 UTC tomorrow.
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].utc_offset, new_york);
+        assert_eq!(buckets[0].send_at.to_rfc3339(), "2024-06-01T13:00:00+00:00");
+        assert_eq!(buckets[1].send_at.to_rfc3339(), "2024-06-02T06:00:00+00:00");
+        assert_eq!(buckets[1].messages, vec!["fi-1", "fi-2"]);
+    }
+}
@@ -0,0 +1,155 @@
+//! Records for the `log` feature. Without the feature the functions do
+//! nothing.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fmt::Display;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+use std::time::Duration;
+
+use crate::client::oauth::OauthError;
+use crate::client::response::FcmResponse;
+use crate::client::retry::AttemptError;
+use crate::message::Target;
+use crate::FcmClientError;
+
+/// Registration token masked to its first and last 4 characters, so that
+/// logs and spans can correlate sends without containing tokens which can
+/// be used to send messages. Tokens shorter than 12 characters are masked
+/// completely.
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub(crate) fn mask_token(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() < 12 {
+        return "…".to_string();
+    }
+    let prefix: String = chars[..4].iter().collect();
+    let suffix: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", prefix, suffix)
+}
+
+/// `error` followed by its [source](std::error::Error::source) chain, like
+/// `Sending failed after 3 retries: Reqwest error: error sending request`.
+pub(crate) fn error_chain(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut chain = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        chain.push_str(": ");
+        chain.push_str(&error.to_string());
+        source = error.source();
+    }
+    chain
+}
+
+#[cfg(feature = "log")]
+fn describe(target: &Target) -> String {
+    match target {
+        Target::Token(token) => format!("token {}", mask_token(token)),
+        Target::Topic(topic) => format!("topic {}", topic),
+        Target::Condition(condition) => format!("condition {}", condition),
+    }
+}
+
+pub(crate) fn send_started(_target: &Target) {
+    #[cfg(feature = "log")]
+    log::debug!("Sending message to {}", describe(_target));
+}
+
+/// Log the data payload of a message, see
+/// [crate::FcmClientBuilder::log_data_payload].
+#[cfg(feature = "log")]
+pub(crate) fn data_payload(target: &Target, data: &serde_json::Value) {
+    log::debug!("Data payload of message to {}: {}", describe(target), data);
+}
+
+pub(crate) fn send_finished(_target: &Target, _result: &Result<FcmResponse, FcmClientError>) {
+    #[cfg(feature = "log")]
+    match _result {
+        Ok(response) => match response.error() {
+            None => log::debug!(
+                "FCM accepted message to {} with HTTP status {}",
+                describe(_target),
+                response.http_status_code()
+            ),
+            Some(error) => log::warn!(
+                "FCM rejected message to {} with HTTP status {}: {:?}",
+                describe(_target),
+                response.http_status_code(),
+                error
+            ),
+        },
+        Err(error) => log::warn!(
+            "Sending message to {} failed: {}",
+            describe(_target),
+            error_chain(error)
+        ),
+    }
+}
+
+pub(crate) fn retry(_target: &Target, _attempt: u32, _wait_time: Duration, _error: &AttemptError) {
+    #[cfg(feature = "log")]
+    log::warn!(
+        "Attempt {} to send message to {} failed with {}, retrying in {:?}",
+        _attempt,
+        describe(_target),
+        _error,
+        _wait_time
+    );
+}
+
+pub(crate) fn access_token_rejected() {
+    #[cfg(feature = "log")]
+    log::debug!("FCM rejected the OAuth access token, refreshing it");
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn access_token_refreshed(_expires_at: Option<impl Display>) {
+    #[cfg(feature = "log")]
+    match _expires_at {
+        Some(expires_at) => log::debug!("Obtained a new OAuth access token, it expires at {}", expires_at),
+        None => log::debug!("Obtained a new OAuth access token"),
+    }
+}
+
+pub(crate) fn access_token_failed(_error: &OauthError) {
+    #[cfg(feature = "log")]
+    log::warn!("Getting an OAuth access token failed: {}", error_chain(_error));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn credentials_reloaded(_path: &Path) {
+    #[cfg(feature = "log")]
+    log::debug!("Reloaded OAuth credentials from {}", _path.display());
+}
+
+#[cfg(all(test, any(feature = "log", feature = "tracing")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_chain_includes_sources() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "key file not found");
+        let error = FcmClientError::Oauth(OauthError::ServiceAccountKeyReadingFailed(io_error));
+
+        assert_eq!(
+            error_chain(&error),
+            "OAuth error: Service account key reading failed: key file not found"
+        );
+    }
+
+    #[test]
+    fn test_mask_token_keeps_first_and_last_characters() {
+        assert_eq!(mask_token("fG3kL9pQzW1xY2"), "fG3k…1xY2");
+        assert_eq!(mask_token("short-token"), "…");
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_describe_target() {
+        assert_eq!(
+            describe(&Target::Token("fG3kL9pQzW1xY2".to_string())),
+            "token fG3k…1xY2"
+        );
+        assert_eq!(describe(&Target::Topic("news".to_string())), "topic news");
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::client::response::{FcmResponse, FcmResponseError};
+use crate::client::telemetry::{outcome_label, retry_count};
+use crate::FcmClientError;
+
+/// Result of one [crate::FcmClient::send], including its retries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SendEvent {
+    /// `SUCCESS`, an FCM error code or `CLIENT_ERROR` for sends which
+    /// failed without an FCM response.
+    pub outcome: &'static str,
+    /// FCM error detected from the response.
+    pub error: Option<FcmResponseError>,
+    /// `None` if the send failed without an FCM response.
+    pub http_status_code: Option<u16>,
+    /// Time from the start of the first attempt to the result.
+    pub latency: Duration,
+    pub retries: u32,
+}
+
+impl SendEvent {
+    pub(crate) fn new(result: &Result<FcmResponse, FcmClientError>, latency: Duration) -> Self {
+        let response = result.as_ref().ok();
+        Self {
+            outcome: outcome_label(result),
+            error: response.and_then(FcmResponse::error),
+            http_status_code: response.map(FcmResponse::http_status_code),
+            latency,
+            retries: retry_count(result) as u32,
+        }
+    }
+}
+
+/// Observer which is called after every send, for example to update
+/// metrics. Set with [crate::FcmClientBuilder::metrics_observer].
+///
+/// The observer is called on the sending task, so it should only update
+/// counters or similar. Use [crate::FcmClientBuilder::telemetry] for
+/// aggregated snapshots instead.
+pub trait MetricsObserver: Send + Sync {
+    fn on_send(&self, event: &SendEvent);
+
+    /// Called when [crate::FcmClientBuilder::adaptive_throttling] changes
+    /// the limit of concurrent send attempts.
+    fn on_concurrency_change(&self, _concurrency_limit: usize) {}
+}
+
+#[derive(Clone)]
+pub(crate) struct ObserverHook(pub Arc<dyn MetricsObserver>);
+
+impl fmt::Debug for ObserverHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ObserverHook")
+    }
+}
+
+#[cfg(feature = "prometheus")]
+pub use self::prometheus_observer::PrometheusObserver;
+
+#[cfg(feature = "prometheus")]
+mod prometheus_observer {
+    use prometheus::{Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry};
+
+    use super::*;
+    use crate::client::telemetry::LATENCY_BUCKETS_MS;
+
+    /// [MetricsObserver] which updates Prometheus metrics:
+    ///
+    /// - `fcm_sends_total` counter with an `outcome` label
+    /// - `fcm_send_retries_total` counter
+    /// - `fcm_send_duration_seconds` histogram
+    /// - `fcm_send_concurrency_limit` gauge, with
+    ///   [crate::FcmClientBuilder::adaptive_throttling]
+    #[derive(Debug, Clone)]
+    pub struct PrometheusObserver {
+        sends: IntCounterVec,
+        retries: IntCounter,
+        duration: Histogram,
+        concurrency_limit: IntGauge,
+    }
+
+    impl PrometheusObserver {
+        /// Create the metrics and register them to `registry`.
+        pub fn new(registry: &Registry) -> prometheus::Result<Self> {
+            let sends = IntCounterVec::new(
+                Opts::new("fcm_sends_total", "Messages sent to FCM by outcome"),
+                &["outcome"],
+            )?;
+            let retries = IntCounter::new("fcm_send_retries_total", "Retried FCM send attempts")?;
+            let buckets = LATENCY_BUCKETS_MS.iter().map(|ms| *ms as f64 / 1000.0).collect();
+            let duration = Histogram::with_opts(
+                HistogramOpts::new("fcm_send_duration_seconds", "Duration of FCM sends including retries")
+                    .buckets(buckets),
+            )?;
+            let concurrency_limit = IntGauge::new(
+                "fcm_send_concurrency_limit",
+                "Limit of concurrent FCM send attempts set by adaptive throttling",
+            )?;
+            registry.register(Box::new(sends.clone()))?;
+            registry.register(Box::new(retries.clone()))?;
+            registry.register(Box::new(duration.clone()))?;
+            registry.register(Box::new(concurrency_limit.clone()))?;
+            Ok(Self {
+                sends,
+                retries,
+                duration,
+                concurrency_limit,
+            })
+        }
+    }
+
+    impl MetricsObserver for PrometheusObserver {
+        fn on_send(&self, event: &SendEvent) {
+            self.sends.with_label_values(&[event.outcome]).inc();
+            self.retries.inc_by(event.retries.into());
+            self.duration.observe(event.latency.as_secs_f64());
+        }
+
+        fn on_concurrency_change(&self, concurrency_limit: usize) {
+            self.concurrency_limit.set(concurrency_limit as i64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_event_from_result() {
+        let event = SendEvent::new(
+            &Ok(FcmResponse::new(404, Default::default(), None)),
+            Duration::from_millis(20),
+        );
+        assert_eq!(event.outcome, "UNREGISTERED");
+        assert_eq!(event.error, Some(FcmResponseError::Unregistered));
+        assert_eq!(event.http_status_code, Some(404));
+
+        let event = SendEvent::new(&Err(FcmClientError::Closed), Duration::ZERO);
+        assert_eq!(event.outcome, "CLIENT_ERROR");
+        assert_eq!(event.http_status_code, None);
+    }
+
+    #[cfg(feature = "prometheus")]
+    #[test]
+    fn test_prometheus_observer_counts_outcomes() {
+        let registry = prometheus::Registry::new();
+        let observer = PrometheusObserver::new(&registry).unwrap();
+        let event = SendEvent::new(
+            &Ok(FcmResponse::new(200, Default::default(), None)),
+            Duration::from_millis(20),
+        );
+        observer.on_send(&event);
+        observer.on_send(&event);
+
+        let families = registry.gather();
+        let sends = families.iter().find(|f| f.get_name() == "fcm_sends_total").unwrap();
+        assert_eq!(sends.get_metric()[0].get_counter().get_value(), 2.0);
+    }
+}
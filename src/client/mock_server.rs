@@ -0,0 +1,249 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Project ID used in the send URL when the client has no credentials.
+pub(crate) const MOCK_PROJECT_ID: &str = "mock-project";
+
+/// Reply of the [MockServer] to one send request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockReply {
+    /// HTTP 200 with the message name.
+    Success,
+    /// HTTP 404 with error code `UNREGISTERED`.
+    Unregistered,
+    /// HTTP 429 with error code `QUOTA_EXCEEDED` and optional
+    /// `Retry-After` header.
+    QuotaExceeded { retry_after: Option<Duration> },
+    /// HTTP 500 with error code `INTERNAL`.
+    Internal,
+    /// HTTP 503 with error code `UNAVAILABLE`.
+    Unavailable,
+    /// Any status and JSON body.
+    Custom { http_status_code: u16, body: Value },
+}
+
+impl MockReply {
+    fn error_body(http_status_code: u16, status: &str, error_code: &str) -> Value {
+        json!({
+            "error": {
+                "code": http_status_code,
+                "status": status,
+                "details": [{
+                    "@type": "type.googleapis.com/google.firebase.fcm.v1.FcmError",
+                    "errorCode": error_code
+                }]
+            }
+        })
+    }
+
+    fn into_response(self, project_id: &str, message_number: usize) -> (u16, Option<Duration>, Value) {
+        match self {
+            MockReply::Success => (
+                200,
+                None,
+                json!({ "name": format!("projects/{}/messages/{}", project_id, message_number) }),
+            ),
+            MockReply::Unregistered => (404, None, Self::error_body(404, "NOT_FOUND", "UNREGISTERED")),
+            MockReply::QuotaExceeded { retry_after } => (
+                429,
+                retry_after,
+                Self::error_body(429, "RESOURCE_EXHAUSTED", "QUOTA_EXCEEDED"),
+            ),
+            MockReply::Internal => (500, None, Self::error_body(500, "INTERNAL", "INTERNAL")),
+            MockReply::Unavailable => (503, None, Self::error_body(503, "UNAVAILABLE", "UNAVAILABLE")),
+            MockReply::Custom { http_status_code, body } => (http_status_code, None, body),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    replies: VecDeque<MockReply>,
+    requests: Vec<Value>,
+}
+
+/// In-process HTTP server which mimics the FCM v1 send endpoint. Point a
+/// client at it with [crate::FcmClientBuilder::endpoint].
+///
+/// Replies are returned in the order they are queued, and requests after
+/// the queue runs out succeed.
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use fcm::message::{Message, Target};
+/// use fcm::{FcmClient, MockReply, MockServer};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let server = MockServer::start().await?;
+/// server.reply(MockReply::QuotaExceeded { retry_after: Some(Duration::from_secs(1)) });
+/// server.reply(MockReply::Unregistered);
+///
+/// let client = FcmClient::builder().endpoint(server.url()).build().await?;
+/// let message = Message::builder(Target::Token("token".to_string())).build();
+/// assert_eq!(client.send(&message).await?.http_status_code(), 429);
+/// # Ok(())
+/// # }
+/// ```
+pub struct MockServer {
+    url: String,
+    state: Arc<Mutex<MockState>>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Start the server on a random local port.
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let state = Arc::new(Mutex::new(MockState::default()));
+        let task = tokio::spawn(Self::serve(listener, state.clone()));
+        Ok(Self { url, state, task })
+    }
+
+    /// Base URL of the server.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Queue a reply for the next request which has no reply yet.
+    pub fn reply(&self, reply: MockReply) {
+        self.state.lock().unwrap().replies.push_back(reply);
+    }
+
+    /// Queue several replies, see [MockServer::reply].
+    pub fn replies(&self, replies: impl IntoIterator<Item = MockReply>) {
+        self.state.lock().unwrap().replies.extend(replies);
+    }
+
+    /// JSON bodies of the requests received so far.
+    pub fn requests(&self) -> Vec<Value> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    async fn serve(listener: TcpListener, state: Arc<Mutex<MockState>>) {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(Self::handle(stream, state.clone()));
+        }
+    }
+
+    async fn handle(mut stream: TcpStream, state: Arc<Mutex<MockState>>) {
+        let (path, body) = match read_request(&mut stream).await {
+            Ok(request) => request,
+            Err(_) => return,
+        };
+        // /v1/projects/{project_id}/messages:send
+        let project_id = path.split('/').nth(3).unwrap_or(MOCK_PROJECT_ID).to_string();
+
+        let (http_status_code, retry_after, body) = {
+            let mut state = state.lock().unwrap();
+            state
+                .requests
+                .push(serde_json::from_slice(&body).unwrap_or(Value::Null));
+            let message_number = state.requests.len();
+            let reply = state.replies.pop_front().unwrap_or(MockReply::Success);
+            reply.into_response(&project_id, message_number)
+        };
+
+        let body = body.to_string();
+        let mut response = format!(
+            "HTTP/1.1 {} Mock\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n",
+            http_status_code,
+            body.len()
+        );
+        if let Some(retry_after) = retry_after {
+            response.push_str(&format!("retry-after: {}\r\n", retry_after.as_secs()));
+        }
+        response.push_str("\r\n");
+        response.push_str(&body);
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Read the request path and body of an HTTP/1.1 request.
+pub(crate) async fn read_request(stream: &mut (impl AsyncRead + Unpin)) -> std::io::Result<(String, Vec<u8>)> {
+    let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid HTTP request");
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 4096];
+    let header_end = loop {
+        if let Some(index) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break index + 4;
+        }
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(invalid());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = std::str::from_utf8(&buffer[..header_end]).map_err(|_| invalid())?;
+    let path = head.split(' ').nth(1).ok_or_else(invalid)?.to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let mut body = buffer.split_off(header_end);
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(invalid());
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    Ok((path, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::response::{FcmResponseError, RetryAfter};
+    use crate::message::{Message, Target};
+    use crate::FcmClient;
+
+    #[tokio::test]
+    async fn test_mock_server_replies_in_order() {
+        let server = MockServer::start().await.unwrap();
+        server.replies([
+            MockReply::Unregistered,
+            MockReply::QuotaExceeded {
+                retry_after: Some(Duration::from_secs(30)),
+            },
+            MockReply::Internal,
+        ]);
+        let client = FcmClient::builder().endpoint(server.url()).build().await.unwrap();
+        let message = Message::builder(Target::Token("token".to_string())).build();
+
+        let response = client.send(&message).await.unwrap();
+        assert_eq!(response.error(), Some(FcmResponseError::Unregistered));
+        let response = client.send(&message).await.unwrap();
+        assert_eq!(response.error(), Some(FcmResponseError::QuotaExceeded));
+        assert_eq!(
+            response.retry_after(),
+            Some(&RetryAfter::Delay(Duration::from_secs(30)))
+        );
+        let response = client.send(&message).await.unwrap();
+        assert_eq!(response.error(), Some(FcmResponseError::Internal));
+        let response = client.send(&message).await.unwrap();
+        assert_eq!(response.error(), None);
+        assert_eq!(response.json()["name"], "projects/mock-project/messages/4");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0]["message"]["token"], "token");
+    }
+}
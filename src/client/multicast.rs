@@ -0,0 +1,170 @@
+//! Sending one message to many registration tokens.
+
+use crate::client::response::{FcmResponse, FcmResponseError};
+use crate::client::retry::RetryPolicy;
+use crate::message::{Message, Target};
+use crate::{FcmClient, FcmClientError};
+
+/// Results of [FcmClient::send_multicast] with the token of every result,
+/// in the order of the tokens.
+#[derive(Debug)]
+pub struct MulticastResult {
+    tokens: Vec<String>,
+    responses: Vec<Result<FcmResponse, FcmClientError>>,
+}
+
+impl MulticastResult {
+    pub(crate) fn new(tokens: Vec<String>, responses: Vec<Result<FcmResponse, FcmClientError>>) -> Self {
+        Self { tokens, responses }
+    }
+
+    /// Number of tokens.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Number of messages which FCM accepted.
+    pub fn success_count(&self) -> usize {
+        self.responses
+            .iter()
+            .filter(|result| matches!(result, Ok(response) if response.error().is_none()))
+            .count()
+    }
+
+    /// Number of messages which FCM rejected or which could not be sent.
+    pub fn failure_count(&self) -> usize {
+        self.len() - self.success_count()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Result<FcmResponse, FcmClientError>)> {
+        self.tokens.iter().map(String::as_str).zip(&self.responses)
+    }
+
+    /// Tokens which should be removed because the app instance was
+    /// unregistered or the token belongs to another sender.
+    ///
+    /// Tokens which failed with [FcmResponseError::InvalidArgument] are not
+    /// included, because FCM also returns the error for an invalid message,
+    /// which would fail for every token. Use [FcmClient::verify_token] to
+    /// check them.
+    pub fn invalid_tokens(&self) -> Vec<&str> {
+        self.iter()
+            .filter(|(_, result)| match result {
+                Ok(response) => matches!(
+                    response.error(),
+                    Some(FcmResponseError::Unregistered | FcmResponseError::SenderIdMismatch)
+                ),
+                Err(_) => false,
+            })
+            .map(|(token, _)| token)
+            .collect()
+    }
+
+    /// Tokens whose message can be sent again later, for example after
+    /// [FcmResponseError::Unavailable], a timeout or
+    /// [FcmClientError::RetriesFailed].
+    pub fn retryable_failures(&self) -> Vec<(&str, &Result<FcmResponse, FcmClientError>)> {
+        self.iter().filter(|(_, result)| is_retryable(result)).collect()
+    }
+}
+
+impl IntoIterator for MulticastResult {
+    type Item = (String, Result<FcmResponse, FcmClientError>);
+    type IntoIter = std::iter::Zip<std::vec::IntoIter<String>, std::vec::IntoIter<Result<FcmResponse, FcmClientError>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tokens.into_iter().zip(self.responses)
+    }
+}
+
+fn is_retryable(result: &Result<FcmResponse, FcmClientError>) -> bool {
+    matches!(result, Err(FcmClientError::RetriesFailed { .. })) || RetryPolicy::new(1).retry_delay(1, result).is_some()
+}
+
+impl FcmClient {
+    /// Send `message` to every token in `tokens`, like
+    /// [FcmClient::send_each]. The target of `message` is replaced with
+    /// each token.
+    pub async fn send_multicast<I>(&self, message: &Message, tokens: I) -> MulticastResult
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let tokens: Vec<String> = tokens.into_iter().map(Into::into).collect();
+        let messages = tokens.iter().map(|token| Message {
+            target: Target::Token(token.clone()),
+            ..message.clone()
+        });
+        let responses = self.send_batch(messages, self.send_params(), None).await;
+        MulticastResult::new(tokens, responses.into_responses())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(http_status_code: u16, body: serde_json::Value) -> Result<FcmResponse, FcmClientError> {
+        Ok(FcmResponse::new(
+            http_status_code,
+            body.as_object().unwrap().clone(),
+            None,
+        ))
+    }
+
+    #[test]
+    fn test_multicast_result_partitions_tokens() {
+        let result = MulticastResult::new(
+            vec!["a", "b", "c", "d", "e"].into_iter().map(String::from).collect(),
+            vec![
+                response(200, json!({ "name": "1" })),
+                response(404, json!({})),
+                response(503, json!({})),
+                response(400, json!({})),
+                Err(FcmClientError::Cancelled),
+            ],
+        );
+
+        assert_eq!(1, result.success_count());
+        assert_eq!(4, result.failure_count());
+        assert_eq!(vec!["b"], result.invalid_tokens());
+        let retryable: Vec<&str> = result
+            .retryable_failures()
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect();
+        assert_eq!(vec!["c"], retryable);
+        let tokens: Vec<String> = result.into_iter().map(|(token, _)| token).collect();
+        assert_eq!(vec!["a", "b", "c", "d", "e"], tokens);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[tokio::test]
+    async fn test_send_multicast_targets_every_token() {
+        use crate::{MockReply, MockServer};
+
+        let server = MockServer::start().await.unwrap();
+        let client = FcmClient::builder()
+            .endpoint(server.url())
+            .batch_concurrency(1)
+            .build()
+            .await
+            .unwrap();
+        server.replies([MockReply::Success, MockReply::Unregistered]);
+        let message = Message::builder(Target::Topic("unused".to_string()))
+            .data_entry("id", "1")
+            .build();
+
+        let result = client.send_multicast(&message, vec!["a", "b"]).await;
+
+        assert_eq!(1, result.success_count());
+        assert_eq!(vec!["b"], result.invalid_tokens());
+        let requests = server.requests();
+        assert_eq!(requests[1]["message"], json!({ "token": "b", "data": { "id": "1" } }));
+    }
+}
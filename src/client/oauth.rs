@@ -1,86 +1,550 @@
-use std::path::PathBuf;
+use std::fmt;
+use std::sync::{Arc, RwLock};
 
-use yup_oauth2::authenticator::{Authenticator, DefaultHyperClient, HyperClientBuilder};
-use yup_oauth2::hyper::client::HttpConnector;
-use yup_oauth2::hyper_rustls::HttpsConnector;
-use yup_oauth2::ServiceAccountAuthenticator;
+use crate::client::CredentialSource;
 
-const FIREBASE_OAUTH_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use self::service_account::*;
 
 #[derive(thiserror::Error, Debug)]
 pub enum OauthError {
-    #[error("Service account key reading failed: {0}")]
-    ServiceAccountKeyReadingFailed(std::io::Error),
-    #[error("OAuth error: {0}")]
+    #[error("Service account key reading failed")]
+    ServiceAccountKeyReadingFailed(#[source] std::io::Error),
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error("OAuth provider error")]
     Oauth(#[from] yup_oauth2::Error),
     #[error("Access token is missing")]
     AccessTokenIsMissing,
-    #[error("Authenticator creation failed: {0}")]
-    AuthenticatorCreatingFailed(std::io::Error),
+    #[error("Authenticator creation failed")]
+    AuthenticatorCreatingFailed(#[source] std::io::Error),
     #[error("Service account key JSON does not contain project ID")]
     ProjectIdIsMissing,
+    #[error("Client is not configured with credentials")]
+    CredentialsAreMissing,
 }
 
 impl OauthError {
     /// If this is `true` then most likely current service account
     /// key is invalid.
     pub(crate) fn is_access_token_missing_even_if_server_requests_completed(&self) -> bool {
-        matches!(
-            self,
-            OauthError::AccessTokenIsMissing
-                | OauthError::Oauth(yup_oauth2::Error::MissingAccessToken | yup_oauth2::Error::AuthError(_))
-        )
+        match self {
+            OauthError::AccessTokenIsMissing => true,
+            #[cfg(not(target_arch = "wasm32"))]
+            OauthError::Oauth(yup_oauth2::Error::MissingAccessToken | yup_oauth2::Error::AuthError(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Credentials {
+    #[cfg(not(target_arch = "wasm32"))]
+    ServiceAccount(ServiceAccount),
+    /// Token obtained by the host, see [crate::FcmClientBuilder::access_token].
+    AccessToken { access_token: String, project_id: String },
+}
+
+impl Credentials {
+    fn project_id(&self) -> String {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Credentials::ServiceAccount(service_account) => service_account.project_id.clone(),
+            Credentials::AccessToken { project_id, .. } => project_id.clone(),
+        }
     }
 }
 
 pub(crate) struct OauthClient {
-    authenticator: Authenticator<HttpsConnector<HttpConnector>>,
-    project_id: String,
+    credentials: RwLock<Credentials>,
+    /// Where `credentials` were loaded from, shown in
+    /// [crate::FcmClient::effective_config].
+    source: RwLock<CredentialSource>,
+    /// Held while a rejected access token is replaced, so that a burst of
+    /// rejected requests refreshes the token only once.
+    refresh_lock: tokio::sync::Mutex<()>,
+    #[cfg(not(target_arch = "wasm32"))]
+    key_file: KeyFile,
 }
 
 impl OauthClient {
-    pub async fn create_with_key_file(
-        service_account_key_path: PathBuf,
-        token_cache_json_path: Option<PathBuf>,
-    ) -> Result<Self, OauthError> {
-        let file = tokio::fs::read_to_string(&service_account_key_path)
-            .await
-            .map_err(OauthError::ServiceAccountKeyReadingFailed)?;
-        Self::create_with_string_key(file, token_cache_json_path).await
+    pub fn create_with_access_token(project_id: String, access_token: String) -> Self {
+        OauthClient {
+            credentials: RwLock::new(Credentials::AccessToken {
+                access_token,
+                project_id,
+            }),
+            source: RwLock::new(CredentialSource::AccessToken),
+            refresh_lock: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            key_file: KeyFile::default(),
+        }
     }
 
-    pub async fn create_with_string_key(
-        service_account_key_json_string: String,
-        token_cache_json_path: Option<PathBuf>,
-    ) -> Result<Self, OauthError> {
-        let key = yup_oauth2::parse_service_account_key(service_account_key_json_string)
-            .map_err(OauthError::ServiceAccountKeyReadingFailed)?;
-        let oauth_client = DefaultHyperClient.build_hyper_client().map_err(OauthError::Oauth)?;
-        let builder = ServiceAccountAuthenticator::with_client(key.clone(), oauth_client);
-        let builder = if let Some(path) = token_cache_json_path {
-            builder.persist_tokens_to_disk(path)
-        } else {
-            builder
+    /// Replace the access token given by the host. A key file which the
+    /// credentials were loaded from is no longer reloaded.
+    pub fn set_access_token(&self, access_token: String) {
+        let mut credentials = self.credentials.write().unwrap();
+        let project_id = credentials.project_id();
+        *credentials = Credentials::AccessToken {
+            access_token,
+            project_id,
         };
-        let authenticator = builder.build().await.map_err(OauthError::AuthenticatorCreatingFailed)?;
+        #[cfg(not(target_arch = "wasm32"))]
+        self.key_file.unwatch();
+        *self.source.write().unwrap() = CredentialSource::AccessToken;
+    }
 
-        let project_id = key.project_id.ok_or(OauthError::ProjectIdIsMissing)?;
+    async fn request_access_token(&self) -> Result<String, OauthError> {
+        let credentials = self.credentials.read().unwrap().clone();
+        match credentials {
+            #[cfg(not(target_arch = "wasm32"))]
+            Credentials::ServiceAccount(service_account) => service_account.request_access_token().await,
+            Credentials::AccessToken { access_token, .. } => Ok(access_token),
+        }
+    }
 
-        Ok(OauthClient {
-            authenticator,
-            project_id,
-        })
+    /// Replace the access token after FCM rejected `rejected_token`, for
+    /// example because it expired or was revoked. Returns `false` if there
+    /// is no other token to retry with, which is the case when the token
+    /// was set by the host and has not been replaced since.
+    pub async fn refresh_access_token(&self, rejected_token: &str) -> Result<bool, OauthError> {
+        let _refreshing = self.refresh_lock.lock().await;
+        let credentials = self.credentials.read().unwrap().clone();
+        match credentials {
+            #[cfg(not(target_arch = "wasm32"))]
+            Credentials::ServiceAccount(service_account) => {
+                // Another request may have refreshed the token already.
+                if service_account.request_access_token().await? == rejected_token {
+                    service_account.force_refresh_access_token().await?;
+                }
+                Ok(true)
+            }
+            Credentials::AccessToken { access_token, .. } => Ok(access_token != rejected_token),
+        }
+    }
+
+    pub fn get_project_id(&self) -> String {
+        self.credentials.read().unwrap().project_id()
+    }
+
+    pub fn credential_source(&self) -> CredentialSource {
+        self.source.read().unwrap().clone()
     }
 
+    #[cfg(target_arch = "wasm32")]
     pub async fn get_access_token(&self) -> Result<String, OauthError> {
-        let scopes = [FIREBASE_OAUTH_SCOPE];
-        let access_token = self.authenticator.token(&scopes).await?;
-        let access_token = access_token.token().ok_or(OauthError::AccessTokenIsMissing)?;
+        self.request_access_token().await
+    }
+}
+
+/// Credentials of a client which other clients can share, so that they
+/// use the same cached access token and refresh it only once. Get them
+/// with [crate::FcmClient::shared_credentials] and pass them to
+/// [crate::FcmClientBuilder::shared_credentials].
+///
+/// Reloading the credentials or setting an access token on one of the
+/// clients changes them for all of them.
+#[derive(Clone)]
+pub struct SharedCredentials {
+    pub(crate) oauth_client: Arc<OauthClient>,
+}
+
+impl fmt::Debug for SharedCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedCredentials")
+            .field("project_id", &self.oauth_client.get_project_id())
+            .field("source", &self.oauth_client.credential_source())
+            .finish()
+    }
+}
+
+/// Service account key authentication, which signs JWTs with `ring` and
+/// is not available on wasm32.
+#[cfg(not(target_arch = "wasm32"))]
+mod service_account {
+    use std::collections::hash_map::DefaultHasher;
+    use std::fmt;
+    use std::hash::{Hash, Hasher};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex, RwLock};
+    use std::time::{Duration, SystemTime};
+
+    use yup_oauth2::authenticator::DefaultAuthenticator;
+    use yup_oauth2::{AccessToken, ServiceAccountAuthenticator, ServiceAccountKey};
+
+    use super::{Credentials, OauthClient, OauthError};
+    use crate::client::logging;
+    use crate::client::rt::{self, Instant};
+    use crate::client::tls::TlsConfig;
+    use crate::client::CredentialSource;
+
+    const FIREBASE_OAUTH_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+
+    /// Minimum time between checks whether the key file has changed.
+    const KEY_FILE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+    #[derive(Clone)]
+    pub(crate) struct ServiceAccount {
+        authenticator: DefaultAuthenticator,
+        pub project_id: String,
+        /// Hash of the last access token, to log when the authenticator
+        /// refreshes it.
+        last_token: Arc<AtomicU64>,
+    }
+
+    /// Authenticator of the application, set with
+    /// [crate::FcmClientBuilder::authenticator].
+    #[derive(Clone)]
+    pub(crate) struct SharedAuthenticator {
+        pub authenticator: DefaultAuthenticator,
+        pub project_id: String,
+    }
+
+    impl fmt::Debug for SharedAuthenticator {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("SharedAuthenticator")
+                .field("project_id", &self.project_id)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl ServiceAccount {
+        pub async fn request_access_token(&self) -> Result<String, OauthError> {
+            let scopes = [FIREBASE_OAUTH_SCOPE];
+            let access_token = self.authenticator.token(&scopes).await?;
+            Ok(self.observe(&access_token)?.to_string())
+        }
+
+        pub async fn force_refresh_access_token(&self) -> Result<(), OauthError> {
+            let scopes = [FIREBASE_OAUTH_SCOPE];
+            let access_token = self.authenticator.force_refreshed_token(&scopes).await?;
+            self.observe(&access_token)?;
+            Ok(())
+        }
+
+        /// Token of `access_token`, logged if it differs from the last one.
+        fn observe<'a>(&self, access_token: &'a AccessToken) -> Result<&'a str, OauthError> {
+            let token = access_token.token().ok_or(OauthError::AccessTokenIsMissing)?;
+            let mut hasher = DefaultHasher::new();
+            token.hash(&mut hasher);
+            let token_hash = hasher.finish();
+            if self.last_token.swap(token_hash, Ordering::Relaxed) != token_hash {
+                logging::access_token_refreshed(access_token.expiration_time());
+            }
+            Ok(token)
+        }
+    }
 
-        Ok(access_token.to_string())
+    /// Identity of the key file contents. Kubernetes replaces mounted secrets
+    /// by swapping a symlink, which changes the inode of the resolved file.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(super) struct KeyFileFingerprint {
+        modified: Option<SystemTime>,
+        len: u64,
+        #[cfg(unix)]
+        inode: u64,
     }
 
-    pub fn get_project_id(&self) -> &str {
-        &self.project_id
+    impl KeyFileFingerprint {
+        pub(super) async fn read(path: &Path) -> std::io::Result<Self> {
+            let metadata = rt::metadata(path).await?;
+            Ok(Self {
+                modified: metadata.modified().ok(),
+                len: metadata.len(),
+                #[cfg(unix)]
+                inode: std::os::unix::fs::MetadataExt::ino(&metadata),
+            })
+        }
+    }
+
+    /// Key file which the current credentials were loaded from.
+    struct WatchedFile {
+        path: PathBuf,
+        fingerprint: Option<KeyFileFingerprint>,
+        checked_at: Instant,
+    }
+
+    #[derive(Default)]
+    pub(crate) struct KeyFile {
+        /// `None` if the credentials were not loaded from a file, or were
+        /// replaced by a key JSON or an access token since.
+        watched: Mutex<Option<WatchedFile>>,
+        /// Reload the watched file when it changes.
+        reload_on_change: bool,
+        token_cache_json_path: Option<PathBuf>,
+        tls: TlsConfig,
+    }
+
+    impl KeyFile {
+        /// Watch `path`, which the credentials were just loaded from.
+        fn watch(&self, path: PathBuf, fingerprint: Option<KeyFileFingerprint>) {
+            *self.watched.lock().unwrap() = Some(WatchedFile {
+                path,
+                fingerprint,
+                checked_at: Instant::now(),
+            });
+        }
+
+        pub(super) fn unwatch(&self) {
+            *self.watched.lock().unwrap() = None;
+        }
+
+        fn path(&self) -> Option<PathBuf> {
+            self.watched
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|watched| watched.path.clone())
+        }
+    }
+
+    impl OauthClient {
+        /// Create the client from `service_account_key_json_string` already
+        /// read from `service_account_key_path`, which is kept for reloads.
+        pub async fn create_with_key_file(
+            service_account_key_path: PathBuf,
+            service_account_key_json_string: String,
+            token_cache_json_path: Option<PathBuf>,
+            tls: TlsConfig,
+            reload_on_change: bool,
+        ) -> Result<Self, OauthError> {
+            let fingerprint = KeyFileFingerprint::read(&service_account_key_path).await.ok();
+            let mut oauth_client =
+                Self::create_with_string_key(service_account_key_json_string, token_cache_json_path, tls).await?;
+            oauth_client.key_file.reload_on_change = reload_on_change;
+            *oauth_client.source.get_mut().unwrap() = CredentialSource::KeyFile {
+                path: service_account_key_path.clone(),
+                reload_on_change,
+            };
+            oauth_client.key_file.watch(service_account_key_path, fingerprint);
+            Ok(oauth_client)
+        }
+
+        pub async fn create_with_string_key(
+            service_account_key_json_string: String,
+            token_cache_json_path: Option<PathBuf>,
+            tls: TlsConfig,
+        ) -> Result<Self, OauthError> {
+            let credentials =
+                Self::create_credentials(service_account_key_json_string, token_cache_json_path.clone(), &tls).await?;
+            Ok(Self::with_service_account(
+                credentials,
+                CredentialSource::KeyJson,
+                token_cache_json_path,
+                tls,
+            ))
+        }
+
+        pub async fn create_with_key(
+            key: ServiceAccountKey,
+            token_cache_json_path: Option<PathBuf>,
+            tls: TlsConfig,
+        ) -> Result<Self, OauthError> {
+            let credentials = Self::credentials_from_key(key, token_cache_json_path.clone(), &tls).await?;
+            Ok(Self::with_service_account(
+                credentials,
+                CredentialSource::KeyJson,
+                token_cache_json_path,
+                tls,
+            ))
+        }
+
+        /// Share the authenticator and token cache of the application.
+        pub fn create_with_authenticator(shared: SharedAuthenticator) -> Self {
+            let credentials = ServiceAccount {
+                authenticator: shared.authenticator,
+                project_id: shared.project_id,
+                last_token: Arc::default(),
+            };
+            Self::with_service_account(credentials, CredentialSource::Authenticator, None, TlsConfig::default())
+        }
+
+        fn with_service_account(
+            credentials: ServiceAccount,
+            source: CredentialSource,
+            token_cache_json_path: Option<PathBuf>,
+            tls: TlsConfig,
+        ) -> Self {
+            OauthClient {
+                credentials: RwLock::new(Credentials::ServiceAccount(credentials)),
+                source: RwLock::new(source),
+                refresh_lock: Default::default(),
+                key_file: KeyFile {
+                    token_cache_json_path,
+                    tls,
+                    ..KeyFile::default()
+                },
+            }
+        }
+
+        async fn create_credentials(
+            service_account_key_json_string: String,
+            token_cache_json_path: Option<PathBuf>,
+            tls: &TlsConfig,
+        ) -> Result<ServiceAccount, OauthError> {
+            let key = yup_oauth2::parse_service_account_key(service_account_key_json_string)
+                .map_err(OauthError::ServiceAccountKeyReadingFailed)?;
+            Self::credentials_from_key(key, token_cache_json_path, tls).await
+        }
+
+        async fn credentials_from_key(
+            key: ServiceAccountKey,
+            token_cache_json_path: Option<PathBuf>,
+            tls: &TlsConfig,
+        ) -> Result<ServiceAccount, OauthError> {
+            let oauth_client = tls.oauth_http_client()?;
+            let builder = ServiceAccountAuthenticator::with_client(key.clone(), oauth_client);
+            let builder = if let Some(path) = token_cache_json_path {
+                builder.persist_tokens_to_disk(path)
+            } else {
+                builder
+            };
+            let authenticator = builder.build().await.map_err(OauthError::AuthenticatorCreatingFailed)?;
+
+            let project_id = key.project_id.ok_or(OauthError::ProjectIdIsMissing)?;
+
+            Ok(ServiceAccount {
+                authenticator,
+                project_id,
+                last_token: Arc::default(),
+            })
+        }
+
+        /// Replace the credentials with a new service account key. A key
+        /// file which the credentials were loaded from is no longer
+        /// reloaded.
+        pub async fn reload(&self, service_account_key_json_string: String) -> Result<(), OauthError> {
+            self.replace_key(service_account_key_json_string).await?;
+            self.key_file.unwatch();
+            *self.source.write().unwrap() = CredentialSource::KeyJson;
+            Ok(())
+        }
+
+        /// Replace the credentials with `service_account_key_json_string`
+        /// read from `path`, which is watched instead of the previous key
+        /// file.
+        pub async fn reload_from_key_file(
+            &self,
+            path: PathBuf,
+            service_account_key_json_string: String,
+        ) -> Result<(), OauthError> {
+            let fingerprint = KeyFileFingerprint::read(&path).await.ok();
+            self.replace_key(service_account_key_json_string).await?;
+            logging::credentials_reloaded(&path);
+            *self.source.write().unwrap() = CredentialSource::KeyFile {
+                path: path.clone(),
+                reload_on_change: self.key_file.reload_on_change,
+            };
+            self.key_file.watch(path, fingerprint);
+            Ok(())
+        }
+
+        async fn replace_key(&self, service_account_key_json_string: String) -> Result<(), OauthError> {
+            let credentials = Self::create_credentials(
+                service_account_key_json_string,
+                self.key_file.token_cache_json_path.clone(),
+                &self.key_file.tls,
+            )
+            .await?;
+            *self.credentials.write().unwrap() = Credentials::ServiceAccount(credentials);
+            Ok(())
+        }
+
+        /// Re-read the watched key file `path`. Does nothing if another
+        /// file is watched by the time the key is loaded.
+        async fn reload_watched(&self, path: &Path) -> Result<(), OauthError> {
+            // Read before the key, so that a change while reading is seen by
+            // the next check.
+            let fingerprint = KeyFileFingerprint::read(path).await.ok();
+            let key = rt::read_to_string(path)
+                .await
+                .map_err(OauthError::ServiceAccountKeyReadingFailed)?;
+            let credentials =
+                Self::create_credentials(key, self.key_file.token_cache_json_path.clone(), &self.key_file.tls).await?;
+
+            let mut watched = self.key_file.watched.lock().unwrap();
+            match watched.as_mut() {
+                Some(watched) if watched.path == path => watched.fingerprint = fingerprint,
+                _ => return Ok(()),
+            }
+            *self.credentials.write().unwrap() = Credentials::ServiceAccount(credentials);
+            drop(watched);
+            logging::credentials_reloaded(path);
+            Ok(())
+        }
+
+        /// Reload credentials if the watched key file has changed. If the new
+        /// key can not be loaded, for example because the file is being
+        /// written, the current credentials are kept and loading is retried
+        /// on the next check.
+        async fn reload_if_changed(&self) {
+            if !self.key_file.reload_on_change {
+                return;
+            }
+            let (path, fingerprint) = {
+                let mut watched = self.key_file.watched.lock().unwrap();
+                let watched = match watched.as_mut() {
+                    Some(watched) if watched.checked_at.elapsed() >= KEY_FILE_CHECK_INTERVAL => watched,
+                    _ => return,
+                };
+                watched.checked_at = Instant::now();
+                (watched.path.clone(), watched.fingerprint.clone())
+            };
+
+            let current = KeyFileFingerprint::read(&path).await.ok();
+            if current.is_some() && current != fingerprint {
+                let _ = self.reload_watched(&path).await;
+            }
+        }
+
+        pub async fn get_access_token(&self) -> Result<String, OauthError> {
+            self.reload_if_changed().await;
+
+            match self.request_access_token().await {
+                // The key may have been rotated and revoked, re-read the key
+                // file once before giving up.
+                Err(error) if error.is_access_token_missing_even_if_server_requests_completed() => {
+                    match self.key_file.path() {
+                        Some(path) if self.reload_watched(&path).await.is_ok() => self.request_access_token().await,
+                        _ => Err(error),
+                    }
+                }
+                result => result,
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::service_account::KeyFileFingerprint;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_key_file_fingerprint_changes_when_file_is_replaced() {
+        let dir = std::env::temp_dir().join(format!("fcm-key-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("key.json");
+        std::fs::write(&path, "{}").unwrap();
+        let original = KeyFileFingerprint::read(&path).await.unwrap();
+        assert_eq!(original, KeyFileFingerprint::read(&path).await.unwrap());
+
+        // Same size, replaced like a rotated secret.
+        let rotated = dir.join("rotated.json");
+        std::fs::write(&rotated, "[]").unwrap();
+        std::fs::rename(&rotated, &path).unwrap();
+
+        assert_ne!(original, KeyFileFingerprint::read(&path).await.unwrap());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_of_host_access_token_needs_a_new_token() {
+        let oauth_client = super::OauthClient::create_with_access_token("project".to_string(), "old".to_string());
+        assert!(!oauth_client.refresh_access_token("old").await.unwrap());
+
+        oauth_client.set_access_token("new".to_string());
+        assert!(oauth_client.refresh_access_token("old").await.unwrap());
     }
 }
@@ -0,0 +1,165 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::duration::to_protobuf_string;
+use crate::message::{insert_missing, object_entry, DeliveryPriority, Platform};
+
+/// Default TTL and priority for a class of messages, for example
+/// transactional or marketing messages. Profiles are registered with
+/// [crate::FcmClientBuilder::profile] and selected per send with
+/// [crate::FcmClient::send_with_profile].
+///
+/// Values which are already set in the message are not overridden.
+///
+/// | Setting  | Android    | APNs              | Webpush   |
+/// |----------|------------|-------------------|-----------|
+/// | ttl      | `ttl`      | `apns-expiration` | `TTL`     |
+/// | priority | `priority` | `apns-priority`   | `Urgency` |
+///
+/// The priority is mapped to the platforms as described in
+/// [DeliveryPriority].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DeliveryProfile {
+    #[serde(serialize_with = "crate::duration::serialize_option")]
+    ttl: Option<Duration>,
+    priority: Option<DeliveryPriority>,
+    #[serde(serialize_with = "crate::duration::serialize_option")]
+    timeout: Option<Duration>,
+}
+
+impl DeliveryProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Set the priority, either a [DeliveryPriority] or an
+    /// [crate::message::AndroidMessagePriority].
+    pub fn priority(mut self, priority: impl Into<DeliveryPriority>) -> Self {
+        self.priority = Some(priority.into());
+        self
+    }
+
+    /// HTTP request timeout for messages of this profile, for example a
+    /// short timeout for transactional messages and a long one for bulk
+    /// messages. Overrides [crate::FcmClientBuilder::fcm_request_timeout].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn request_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Apply the profile to the serialized `message`. The `excluded`
+    /// platforms, see [crate::message::Message::platforms], get no
+    /// configuration.
+    pub(crate) fn apply(&self, message: &mut Map<String, Value>, excluded: &[Platform], now: SystemTime) {
+        if let Some(priority) = self.priority {
+            priority.fill_missing(message, excluded);
+        }
+
+        if let Some(ttl) = self.ttl {
+            if !excluded.contains(&Platform::Android) {
+                insert_missing(object_entry(message, "android"), "ttl", to_protobuf_string(&ttl));
+            }
+
+            if !excluded.contains(&Platform::Ios) {
+                let expiration = (now + ttl).duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+                let apns_headers = object_entry(object_entry(message, "apns"), "headers");
+                insert_missing(apns_headers, "apns-expiration", expiration.to_string());
+            }
+
+            if !excluded.contains(&Platform::Web) {
+                let webpush_headers = object_entry(object_entry(message, "webpush"), "headers");
+                insert_missing(webpush_headers, "TTL", ttl.as_secs().to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_profile_fills_missing_platform_settings() {
+        let profile = DeliveryProfile::new()
+            .ttl(Duration::from_secs(3600))
+            .priority(crate::message::AndroidMessagePriority::Normal);
+        let mut message = json!({
+            "token": "token",
+            "android": { "priority": "HIGH" },
+        });
+
+        profile.apply(
+            message.as_object_mut().unwrap(),
+            &[],
+            UNIX_EPOCH + Duration::from_secs(1_000),
+        );
+
+        let expected = json!({
+            "token": "token",
+            "android": { "priority": "HIGH", "ttl": "3600s" },
+            "apns": { "headers": { "apns-expiration": "4600", "apns-priority": "5" } },
+            "webpush": { "headers": { "TTL": "3600", "Urgency": "normal" } },
+        });
+        assert_eq!(expected, message);
+    }
+
+    #[test]
+    fn test_profile_without_ttl_and_priority_keeps_message() {
+        let profile = DeliveryProfile::new().timeout(Duration::from_secs(10));
+        let mut message = json!({ "token": "token" });
+
+        profile.apply(message.as_object_mut().unwrap(), &[], UNIX_EPOCH);
+
+        assert_eq!(json!({ "token": "token" }), message);
+    }
+
+    #[test]
+    fn test_profile_priority_respects_background_notifications() {
+        let profile = DeliveryProfile::new().priority(DeliveryPriority::Transactional);
+        let mut background = json!({
+            "token": "token",
+            "notification": { "title": "Sync" },
+            "apns": { "payload": { "aps": { "content-available": 1 } } },
+        });
+        let mut alert = json!({ "token": "token", "notification": { "title": "Your code is 1234" } });
+
+        profile.apply(background.as_object_mut().unwrap(), &[], UNIX_EPOCH);
+        profile.apply(alert.as_object_mut().unwrap(), &[], UNIX_EPOCH);
+
+        assert_eq!(background["apns"]["headers"]["apns-priority"], "5");
+        assert_eq!(background["apns"]["payload"]["aps"].get("interruption-level"), None);
+        assert_eq!(alert["apns"]["headers"]["apns-priority"], "10");
+        assert_eq!(alert["apns"]["payload"]["aps"]["interruption-level"], "time-sensitive");
+    }
+
+    #[test]
+    fn test_profile_skips_excluded_platforms() {
+        let profile = DeliveryProfile::new()
+            .ttl(Duration::from_secs(3600))
+            .priority(DeliveryPriority::High);
+        let mut message = json!({ "token": "token" });
+
+        profile.apply(
+            message.as_object_mut().unwrap(),
+            &[Platform::Ios, Platform::Web],
+            UNIX_EPOCH,
+        );
+
+        assert_eq!(
+            json!({ "token": "token", "android": { "priority": "HIGH", "ttl": "3600s" } }),
+            message
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+use crate::client::response::{FcmResponse, RetryAfter};
+use crate::FcmClientError;
+
+/// HTTP response from FCM before parsing it to [FcmResponse].
+#[derive(Debug)]
+pub(crate) struct RawResponse {
+    pub http_status_code: u16,
+    pub retry_after: Option<String>,
+    /// Not recorded by the VCR, so empty in replayed responses.
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl RawResponse {
+    /// Read status, headers and at most `max_body_size` bytes of body.
+    pub async fn read(
+        #[allow(unused_mut)] mut response: reqwest::Response,
+        max_body_size: usize,
+    ) -> Result<Self, FcmClientError> {
+        let retry_after = match response.headers().get(RETRY_AFTER) {
+            Some(header_value) => Some(
+                header_value
+                    .to_str()
+                    .map_err(|_| FcmClientError::RetryAfterHttpHeaderIsNotString)?
+                    .to_string(),
+            ),
+            None => None,
+        };
+        let http_status_code = response.status().as_u16();
+        let headers = response.headers().clone();
+
+        let too_large = FcmClientError::ResponseBodyTooLarge { limit: max_body_size };
+        if response
+            .content_length()
+            .is_some_and(|length| length > max_body_size as u64)
+        {
+            return Err(too_large);
+        }
+        let mut body = Vec::new();
+        // Return if I/O error occurs
+        #[cfg(not(target_arch = "wasm32"))]
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > max_body_size {
+                return Err(too_large);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        // The fetch API reads the whole body at once.
+        #[cfg(target_arch = "wasm32")]
+        {
+            body.extend_from_slice(&response.bytes().await?);
+            if body.len() > max_body_size {
+                return Err(too_large);
+            }
+        }
+
+        Ok(Self {
+            http_status_code,
+            retry_after,
+            headers,
+            body,
+        })
+    }
+
+    /// FCM rejected the access token of the request, as opposed to
+    /// `THIRD_PARTY_AUTH_ERROR` for APNs or web push credentials which has
+    /// the same HTTP status.
+    pub fn is_access_token_rejected(&self) -> bool {
+        if self.http_status_code != 401 {
+            return false;
+        }
+        let body: serde_json::Value = serde_json::from_slice(&self.body).unwrap_or_default();
+        let details = body["error"]["details"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        !details
+            .iter()
+            .any(|detail| detail["errorCode"] == "THIRD_PARTY_AUTH_ERROR")
+    }
+
+    pub fn into_fcm_response(self) -> Result<FcmResponse, FcmClientError> {
+        let retry_after = if let Some(header_str) = self.retry_after {
+            let value =
+                header_str
+                    .parse::<RetryAfter>()
+                    .map_err(|error| FcmClientError::RetryAfterHttpHeaderInvalid {
+                        error,
+                        value: header_str.clone(),
+                    })?;
+            Some(value)
+        } else {
+            None
+        };
+        let response_json_object = serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(&self.body)
+            .ok()
+            .unwrap_or_default();
+
+        Ok(
+            FcmResponse::new(self.http_status_code, response_json_object, retry_after)
+                .with_raw(self.body, self.headers),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(http_status_code: u16, body: serde_json::Value) -> RawResponse {
+        RawResponse {
+            http_status_code,
+            retry_after: None,
+            headers: HeaderMap::new(),
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    #[test]
+    fn test_access_token_rejection_is_not_third_party_auth_error() {
+        let unauthenticated = serde_json::json!({ "error": { "code": 401, "status": "UNAUTHENTICATED" } });
+        let third_party = serde_json::json!({
+            "error": {
+                "code": 401,
+                "status": "UNAUTHENTICATED",
+                "details": [{ "errorCode": "THIRD_PARTY_AUTH_ERROR" }]
+            }
+        });
+
+        assert!(response(401, unauthenticated.clone()).is_access_token_rejected());
+        assert!(!response(401, third_party).is_access_token_rejected());
+        assert!(!response(403, unauthenticated).is_access_token_rejected());
+    }
+
+    /// Read the response of a local server which answers with `raw_response`.
+    async fn read_from_server(raw_response: &'static str, max_body_size: usize) -> Result<RawResponse, FcmClientError> {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let _ = std::io::Read::read(&mut stream, &mut request);
+            let _ = std::io::Write::write_all(&mut stream, raw_response.as_bytes());
+        });
+        RawResponse::read(reqwest::get(url).await?, max_body_size).await
+    }
+
+    #[tokio::test]
+    async fn test_content_length_over_limit_is_rejected() {
+        let response = "HTTP/1.1 200 OK\r\ncontent-length: 32\r\n\r\n0123456789abcdef0123456789abcdef";
+
+        let result = read_from_server(response, 16).await;
+
+        assert!(matches!(
+            result,
+            Err(FcmClientError::ResponseBodyTooLarge { limit: 16 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_streamed_body_over_limit_is_rejected() {
+        // Without a content length the limit is checked while reading.
+        let response = "HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n\
+                        10\r\n0123456789abcdef\r\n10\r\n0123456789abcdef\r\n0\r\n\r\n";
+
+        let result = read_from_server(response, 20).await;
+        assert!(matches!(
+            result,
+            Err(FcmClientError::ResponseBodyTooLarge { limit: 20 })
+        ));
+
+        let result = read_from_server(response, 32).await;
+        assert_eq!(result.unwrap().body.len(), 32);
+    }
+}
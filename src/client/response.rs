@@ -1,12 +1,20 @@
 use chrono::{DateTime, FixedOffset};
 
 use chrono::Utc;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 use std::time::Duration;
 use std::{
     convert::{TryFrom, TryInto},
     str::FromStr,
 };
 
+use crate::client::raw_response::RawResponse;
+use crate::client::retry::AttemptRecord;
+use crate::client::DEFAULT_MAX_RESPONSE_BODY_SIZE;
+
 /// Error cases which can be detected from [FcmResponse].
 ///
 /// Check <https://firebase.google.com/docs/reference/fcm/rest/v1/ErrorCode>
@@ -128,11 +136,54 @@ impl FromStr for RetryAfter {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Formats the value like the HTTP header. Delays are whole seconds.
+impl fmt::Display for RetryAfter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetryAfter::Delay(duration) => write!(f, "{}", duration.as_secs()),
+            RetryAfter::DateTime(date_time) => f.write_str(&date_time.to_rfc2822()),
+        }
+    }
+}
+
+impl Serialize for RetryAfter {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for RetryAfter {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(d)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Response of FCM to a send request.
+///
+/// It serializes to the status code, the response JSON and the
+/// `Retry-After` header, so it can be stored and read back, for example
+/// in a job queue. The retry history, the other headers and the raw body
+/// are not serialized.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FcmResponse {
     http_status_code: u16,
+    #[serde(rename = "json")]
     response_json_object: serde_json::Map<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     retry_after: Option<RetryAfter>,
+    #[serde(skip)]
+    retry_history: Vec<AttemptRecord>,
+    #[serde(skip)]
+    headers: HeaderMap,
+    #[serde(skip)]
+    body: Vec<u8>,
 }
 
 impl FcmResponse {
@@ -145,11 +196,46 @@ impl FcmResponse {
             http_status_code,
             response_json_object,
             retry_after,
+            retry_history: Vec::new(),
+            headers: HeaderMap::new(),
+            body: Vec::new(),
         }
     }
 
+    /// Keep the body and headers of the HTTP response.
+    pub(crate) fn with_raw(mut self, body: Vec<u8>, headers: HeaderMap) -> Self {
+        self.body = body;
+        self.headers = headers;
+        self
+    }
+
+    /// Create a response, for example to script [crate::MockFcmSender].
+    #[cfg(feature = "test-utils")]
+    pub fn mock(http_status_code: u16, response_json_object: serde_json::Map<String, serde_json::Value>) -> Self {
+        Self::new(http_status_code, response_json_object, None)
+    }
+
+    /// Read a response of FCM to a request made with
+    /// [crate::FcmClient::request_builder].
+    pub async fn from_response(response: reqwest::Response) -> Result<Self, crate::FcmClientError> {
+        RawResponse::read(response, DEFAULT_MAX_RESPONSE_BODY_SIZE)
+            .await?
+            .into_fcm_response()
+    }
+
+    pub(crate) fn with_retry_history(mut self, retry_history: Vec<AttemptRecord>) -> Self {
+        self.retry_history = retry_history;
+        self
+    }
+
+    /// Earlier attempts to send the message which failed and were
+    /// retried. Empty if the message was sent on the first attempt.
+    pub fn retry_history(&self) -> &[AttemptRecord] {
+        &self.retry_history
+    }
+
     /// If `None` then [crate::message::Message] is sent successfully.
-    pub fn recommended_error_handling_action(&self) -> Option<RecomendedAction> {
+    pub fn recommended_error_handling_action(&self) -> Option<RecomendedAction<'_>> {
         RecomendedAction::analyze(self)
     }
 
@@ -162,6 +248,32 @@ impl FcmResponse {
         self.http_status_code
     }
 
+    /// HTTP status of the response. Codes which are not valid HTTP status
+    /// codes, possible only in responses which were deserialized or created
+    /// for tests, are reported as `500 Internal Server Error`.
+    pub fn http_status(&self) -> StatusCode {
+        StatusCode::from_u16(self.http_status_code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    /// Body of the HTTP response exactly as FCM sent it, for logging
+    /// responses which [FcmResponse::json] does not represent, for example
+    /// bodies which are not JSON. Empty in responses which were
+    /// deserialized or created for tests.
+    pub fn raw_body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// [FcmResponse::raw_body] as text, with invalid UTF-8 replaced.
+    pub fn raw_body_text(&self) -> std::borrow::Cow<'_, str> {
+        String::from_utf8_lossy(&self.body)
+    }
+
+    /// Headers of the HTTP response. Empty in responses which were
+    /// deserialized, created for tests or replayed from VCR fixtures.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
     pub fn json(&self) -> &serde_json::Map<String, serde_json::Value> {
         &self.response_json_object
     }
@@ -169,6 +281,118 @@ impl FcmResponse {
     pub fn retry_after(&self) -> Option<&RetryAfter> {
         self.retry_after.as_ref()
     }
+
+    /// Diagnose why APNs rejected the message. `None` unless the error is
+    /// [FcmResponseError::ThirdPartyAuth] or the response contains an
+    /// APNs error.
+    pub fn apns_diagnostic(&self) -> Option<ApnsDiagnostic> {
+        ApnsDiagnostic::detect_from(self.error(), &self.response_json_object)
+    }
+}
+
+/// Cause of an APNs delivery failure, detected from the APNs error which
+/// FCM includes in the error details. [std::fmt::Display] describes how
+/// to fix it.
+///
+/// Check <https://developer.apple.com/documentation/usernotifications/handling-notification-responses-from-apns>
+/// for the APNs error reasons.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApnsDiagnostic {
+    /// APNs reason `BadDeviceToken`. Usually the token belongs to a
+    /// development build, which registers with the APNs sandbox, while
+    /// the message was sent to production or the other way around.
+    SandboxProductionMismatch,
+    /// APNs reason `BadCertificateEnvironment`. The APNs certificate
+    /// uploaded to Firebase is for the other environment.
+    CertificateEnvironmentMismatch,
+    /// APNs reason `MissingTopic`. The `apns-topic` header is required.
+    MissingTopic,
+    /// APNs reason `DeviceTokenNotForTopic` or `TopicDisallowed`. The
+    /// `apns-topic` header does not match the bundle ID of the app.
+    TopicMismatch,
+    /// APNs reason `InvalidProviderToken`, `ExpiredProviderToken`,
+    /// `BadCertificate` or `Forbidden`. The APNs key or certificate
+    /// uploaded to Firebase is not valid.
+    InvalidCredentials,
+    /// Other APNs reason.
+    Other { reason: String },
+    /// FCM reported [FcmResponseError::ThirdPartyAuth] without APNs
+    /// details.
+    Unknown,
+}
+
+impl ApnsDiagnostic {
+    fn detect_from(
+        error: Option<FcmResponseError>,
+        response_json: &serde_json::Map<String, serde_json::Value>,
+    ) -> Option<Self> {
+        let reason = Self::get_apns_reason(response_json);
+        if reason.is_none() && error != Some(FcmResponseError::ThirdPartyAuth) {
+            return None;
+        }
+        let diagnostic = match reason {
+            None => Self::Unknown,
+            Some("BadDeviceToken") => Self::SandboxProductionMismatch,
+            Some("BadCertificateEnvironment") => Self::CertificateEnvironmentMismatch,
+            Some("MissingTopic") => Self::MissingTopic,
+            Some("DeviceTokenNotForTopic" | "TopicDisallowed") => Self::TopicMismatch,
+            Some("InvalidProviderToken" | "ExpiredProviderToken" | "BadCertificate" | "Forbidden") => {
+                Self::InvalidCredentials
+            }
+            Some(reason) => Self::Other {
+                reason: reason.to_string(),
+            },
+        };
+        Some(diagnostic)
+    }
+
+    /// Reason of the `ApnsError` entry in the error details.
+    fn get_apns_reason(response_json: &serde_json::Map<String, serde_json::Value>) -> Option<&str> {
+        response_json
+            .get("error")
+            .and_then(|v| v.get("details"))
+            .and_then(|v| v.as_array())?
+            .iter()
+            .filter(|detail| {
+                detail
+                    .get("@type")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|t| t.ends_with(".ApnsError"))
+            })
+            .find_map(|detail| detail.get("reason").and_then(|v| v.as_str()))
+    }
+}
+
+impl std::fmt::Display for ApnsDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SandboxProductionMismatch => f.write_str(
+                "APNs rejected the device token. Tokens of development builds are valid only in the APNs \
+                 sandbox and tokens of TestFlight and App Store builds only in production, check that the \
+                 app build matches the APNs key or certificate uploaded to Firebase",
+            ),
+            Self::CertificateEnvironmentMismatch => f.write_str(
+                "APNs certificate is for the wrong environment, upload a production certificate to Firebase \
+                 for TestFlight and App Store builds or use an APNs auth key which works in both",
+            ),
+            Self::MissingTopic => {
+                f.write_str("APNs topic is missing, set the apns-topic header to the bundle ID of the app")
+            }
+            Self::TopicMismatch => f.write_str(
+                "APNs topic does not match the app, check that the apns-topic header is the bundle ID of the \
+                 app, with the .voip or .push-type.liveactivity suffix when needed",
+            ),
+            Self::InvalidCredentials => f.write_str(
+                "APNs key or certificate is not valid, check the key ID, team ID and expiry of the APNs \
+                 credentials uploaded to Firebase",
+            ),
+            Self::Other { reason } => write!(f, "APNs rejected the message with reason {}", reason),
+            Self::Unknown => f.write_str(
+                "APNs or web push authentication failed, check the APNs credentials and web push \
+                 certificates uploaded to Firebase",
+            ),
+        }
+    }
 }
 
 /// Error handling action which server or developer should do based on
@@ -219,9 +443,9 @@ pub enum RecomendedAction<'a> {
 }
 
 impl RecomendedAction<'_> {
-    fn analyze(response: &FcmResponse) -> Option<RecomendedAction> {
+    fn analyze(response: &FcmResponse) -> Option<RecomendedAction<'_>> {
         let action = match response.error()? {
-            FcmResponseError::Unspecified | FcmResponseError::Unknown { .. } => RecomendedAction::HandleUnknownError,
+            FcmResponseError::Unspecified | FcmResponseError::Unknown => RecomendedAction::HandleUnknownError,
             FcmResponseError::Unregistered => RecomendedAction::RemoveFcmAppToken,
             FcmResponseError::InvalidArgument => RecomendedAction::FixMessageContent,
             FcmResponseError::SenderIdMismatch => RecomendedAction::CheckSenderIdEquality,
@@ -274,6 +498,50 @@ mod tests {
     use super::*;
     use chrono::DateTime;
 
+    fn apns_error_response(reason: &str) -> FcmResponse {
+        let json = serde_json::json!({
+            "error": {
+                "code": 401,
+                "status": "UNAUTHENTICATED",
+                "details": [
+                    {
+                        "@type": "type.googleapis.com/google.firebase.fcm.v1.FcmError",
+                        "errorCode": "THIRD_PARTY_AUTH_ERROR"
+                    },
+                    {
+                        "@type": "type.googleapis.com/google.firebase.fcm.v1.ApnsError",
+                        "statusCode": 400,
+                        "reason": reason
+                    }
+                ]
+            }
+        });
+        FcmResponse::new(401, json.as_object().unwrap().clone(), None)
+    }
+
+    #[test]
+    fn test_apns_diagnostic_from_apns_error_reason() {
+        assert_eq!(
+            apns_error_response("BadDeviceToken").apns_diagnostic(),
+            Some(ApnsDiagnostic::SandboxProductionMismatch)
+        );
+        assert_eq!(
+            apns_error_response("MissingTopic").apns_diagnostic(),
+            Some(ApnsDiagnostic::MissingTopic)
+        );
+        assert_eq!(
+            apns_error_response("PayloadEmpty").apns_diagnostic(),
+            Some(ApnsDiagnostic::Other {
+                reason: "PayloadEmpty".to_string()
+            })
+        );
+        assert_eq!(
+            FcmResponse::new(401, Default::default(), None).apns_diagnostic(),
+            Some(ApnsDiagnostic::Unknown)
+        );
+        assert_eq!(FcmResponse::new(404, Default::default(), None).apns_diagnostic(), None);
+    }
+
     #[test]
     fn test_retry_after_from_seconds() {
         let expected_wait_time = Duration::from_secs(1);
@@ -334,4 +602,21 @@ mod tests {
             retry_after.wait_time_with_time_provider(|| past_date_time),
         );
     }
+
+    #[test]
+    fn test_response_round_trip() {
+        let json = serde_json::json!({ "error": { "code": 429, "status": "RESOURCE_EXHAUSTED" } });
+        let response = FcmResponse::new(
+            429,
+            json.as_object().unwrap().clone(),
+            Some(RetryAfter::from_str("Sun, 06 Nov 1994 08:49:37 GMT").unwrap()),
+        );
+
+        let serialized = serde_json::to_value(&response).unwrap();
+        assert_eq!(serialized["retry_after"], "Sun, 6 Nov 1994 08:49:37 +0000");
+        let deserialized: FcmResponse = serde_json::from_value(serialized).unwrap();
+
+        assert_eq!(response, deserialized);
+        assert_eq!(Some(FcmResponseError::QuotaExceeded), deserialized.error());
+    }
 }
@@ -0,0 +1,215 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::client::logging::error_chain;
+use crate::client::response::{FcmResponse, FcmResponseError, RecomendedAction, RecomendedWaitTime};
+use crate::FcmClientError;
+
+/// Initial wait time before retrying after a timeout or connection error.
+const TRANSPORT_ERROR_INITIAL_WAIT_TIME: Duration = Duration::from_secs(1);
+
+/// Retry policy for messages which failed with a retryable error.
+///
+/// FCM responses are retried when [FcmResponse::recommended_error_handling_action]
+/// recommends a retry, using the `Retry-After` header or exponential back-off
+/// as the wait time. Request timeouts and connection errors are retried as well.
+///
+/// <https://firebase.google.com/docs/cloud-messaging/scale-fcm#handling-retries>
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    pub(crate) max_wait_time: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// No retries.
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            max_wait_time: Duration::from_secs(60 * 10),
+        }
+    }
+
+    /// Upper limit for a single wait time. Default is 10 minutes.
+    pub fn max_wait_time(mut self, max_wait_time: Duration) -> Self {
+        self.max_wait_time = max_wait_time;
+        self
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Wait time before the next attempt and the reason for retrying, or
+    /// `None` if the result of the attempt number `attempt` (starting from 1)
+    /// should be returned.
+    pub(crate) fn retry_delay(
+        &self,
+        attempt: u32,
+        result: &Result<FcmResponse, FcmClientError>,
+    ) -> Option<(Duration, AttemptError)> {
+        if attempt > self.max_retries {
+            return None;
+        }
+
+        let (wait_time, error) = match result {
+            Ok(response) => {
+                let wait_time = match response.recommended_error_handling_action()? {
+                    RecomendedAction::Retry(wait_time) | RecomendedAction::ReduceMessageRateAndRetry(wait_time) => {
+                        wait_time
+                    }
+                    _ => return None,
+                };
+                let wait_time = match wait_time {
+                    RecomendedWaitTime::SpecificWaitTime(retry_after) => retry_after.wait_time(),
+                    RecomendedWaitTime::InitialWaitTime(initial) => exponential(initial, attempt),
+                };
+                let error = AttemptError::Response {
+                    http_status_code: response.http_status_code(),
+                    error: response.error()?,
+                };
+                (wait_time, error)
+            }
+            Err(FcmClientError::Reqwest(error)) if error.is_timeout() || is_connect(error) => (
+                exponential(TRANSPORT_ERROR_INITIAL_WAIT_TIME, attempt),
+                AttemptError::Transport(error_chain(error)),
+            ),
+            Err(_) => return None,
+        };
+
+        Some((wait_time.min(self.max_wait_time), error))
+    }
+}
+
+/// Connection failures. The fetch API on wasm32 does not tell them apart
+/// from other request errors.
+fn is_connect(error: &reqwest::Error) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    let is_connect = error.is_connect();
+    #[cfg(target_arch = "wasm32")]
+    let is_connect = error.is_request();
+    is_connect
+}
+
+fn exponential(initial: Duration, attempt: u32) -> Duration {
+    initial.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+}
+
+/// Reason why an attempt to send a message failed and will be retried.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttemptError {
+    /// FCM responded with a retryable error.
+    Response {
+        http_status_code: u16,
+        error: FcmResponseError,
+    },
+    /// The request timed out or connecting to FCM failed.
+    Transport(String),
+}
+
+impl fmt::Display for AttemptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttemptError::Response {
+                http_status_code,
+                error,
+            } => {
+                write!(f, "HTTP {}: {:?}", http_status_code, error)
+            }
+            AttemptError::Transport(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Failed attempt which was retried.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttemptRecord {
+    /// Attempt number, starting from 1.
+    pub attempt: u32,
+    /// When the attempt was started.
+    pub started_at: SystemTime,
+    pub error: AttemptError,
+    /// Wait time before the next attempt.
+    pub delay: Duration,
+}
+
+impl AttemptRecord {
+    /// HTTP status code of the FCM response, if FCM responded.
+    pub fn http_status_code(&self) -> Option<u16> {
+        match &self.error {
+            AttemptError::Response { http_status_code, .. } => Some(*http_status_code),
+            AttemptError::Transport(_) => None,
+        }
+    }
+}
+
+type AttemptCallback = dyn Fn(u32, Duration, &AttemptError) + Send + Sync;
+
+/// Callback which is called with the failed attempt number (starting
+/// from 1), the wait time before the next attempt and the failure reason.
+#[derive(Clone)]
+pub(crate) struct AttemptHook(pub Arc<AttemptCallback>);
+
+impl fmt::Debug for AttemptHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AttemptHook")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::response::RetryAfter;
+
+    fn response(http_status_code: u16, retry_after: Option<RetryAfter>) -> Result<FcmResponse, FcmClientError> {
+        Ok(FcmResponse::new(http_status_code, Default::default(), retry_after))
+    }
+
+    #[test]
+    fn test_no_retry_by_default() {
+        assert_eq!(None, RetryPolicy::default().retry_delay(1, &response(503, None)));
+    }
+
+    #[test]
+    fn test_exponential_back_off_for_internal_error() {
+        let policy = RetryPolicy::new(3);
+        let error = AttemptError::Response {
+            http_status_code: 500,
+            error: FcmResponseError::Internal,
+        };
+
+        assert_eq!(
+            Some((Duration::from_secs(10), error.clone())),
+            policy.retry_delay(1, &response(500, None))
+        );
+        assert_eq!(
+            Some((Duration::from_secs(40), error)),
+            policy.retry_delay(3, &response(500, None))
+        );
+        assert_eq!(None, policy.retry_delay(4, &response(500, None)));
+    }
+
+    #[test]
+    fn test_retry_after_header_is_used_and_capped() {
+        let policy = RetryPolicy::new(1).max_wait_time(Duration::from_secs(30));
+        let retry_after = RetryAfter::Delay(Duration::from_secs(120));
+
+        let (wait_time, _) = policy.retry_delay(1, &response(429, Some(retry_after))).unwrap();
+        assert_eq!(Duration::from_secs(30), wait_time);
+    }
+
+    #[test]
+    fn test_non_retryable_errors_are_not_retried() {
+        let policy = RetryPolicy::new(3);
+
+        assert_eq!(None, policy.retry_delay(1, &response(404, None)));
+        assert_eq!(None, policy.retry_delay(1, &Err(FcmClientError::Closed)));
+    }
+}
@@ -0,0 +1,128 @@
+//! Timers, file system and DNS access of the client, provided by the
+//! executor selected with the `runtime-*` features.
+//!
+//! If several runtimes are enabled, async-std is preferred over smol and
+//! both over Tokio, because `runtime-tokio` is a default feature and is
+//! easily enabled by another crate in the dependency graph. On wasm32 the
+//! timers of the host are used and the features are ignored.
+
+use std::future::Future;
+use std::time::Duration;
+
+use futures_util::future::{self, Either};
+
+#[cfg(not(any(
+    target_arch = "wasm32",
+    feature = "runtime-tokio",
+    feature = "runtime-async-std",
+    feature = "runtime-smol"
+)))]
+compile_error!("one of the features runtime-tokio, runtime-async-std or runtime-smol must be enabled");
+
+pub(crate) use self::imp::sleep;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use self::imp::{lookup_host, metadata, read_to_string};
+
+/// `Instant::now` and `SystemTime::now` of `std` panic on
+/// wasm32-unknown-unknown, where the clock of the host is used instead.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::Instant;
+
+/// Current time, see [Instant].
+pub(crate) fn now() -> std::time::SystemTime {
+    #[cfg(not(target_arch = "wasm32"))]
+    let now = std::time::SystemTime::now();
+    #[cfg(target_arch = "wasm32")]
+    let now = std::time::UNIX_EPOCH
+        + web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .unwrap_or_default();
+    now
+}
+
+/// Error of [timeout] when the deadline elapses first.
+#[derive(Debug)]
+pub(crate) struct Elapsed;
+
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+    let future = Box::pin(future);
+    let sleep = Box::pin(sleep(duration));
+    match future::select(future, sleep).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(Elapsed),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    pub use gloo_timers::future::sleep;
+}
+
+#[cfg(all(feature = "runtime-async-std", not(target_arch = "wasm32")))]
+mod imp {
+    use std::io;
+    use std::net::SocketAddr;
+    use std::path::Path;
+
+    pub use async_std::task::sleep;
+
+    pub async fn read_to_string(path: &Path) -> io::Result<String> {
+        async_std::fs::read_to_string(path).await
+    }
+
+    pub async fn metadata(path: &Path) -> io::Result<std::fs::Metadata> {
+        async_std::fs::metadata(path).await
+    }
+
+    pub async fn lookup_host(host: &str) -> io::Result<Vec<SocketAddr>> {
+        let addrs = async_std::net::ToSocketAddrs::to_socket_addrs(&(host, 0)).await?;
+        Ok(addrs.collect())
+    }
+}
+
+/// smol has no async file system or DNS without extra crates, so the small
+/// key files are read and host names resolved with blocking calls.
+#[cfg(all(
+    feature = "runtime-smol",
+    not(any(target_arch = "wasm32", feature = "runtime-async-std"))
+))]
+mod imp {
+    use std::io;
+    use std::net::{SocketAddr, ToSocketAddrs};
+    use std::path::Path;
+    use std::time::Duration;
+
+    pub async fn sleep(duration: Duration) {
+        async_io::Timer::after(duration).await;
+    }
+
+    pub async fn read_to_string(path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    pub async fn metadata(path: &Path) -> io::Result<std::fs::Metadata> {
+        std::fs::metadata(path)
+    }
+
+    pub async fn lookup_host(host: &str) -> io::Result<Vec<SocketAddr>> {
+        Ok((host, 0).to_socket_addrs()?.collect())
+    }
+}
+
+#[cfg(all(
+    feature = "runtime-tokio",
+    not(any(target_arch = "wasm32", feature = "runtime-async-std", feature = "runtime-smol"))
+))]
+mod imp {
+    use std::io;
+    use std::net::SocketAddr;
+
+    pub use tokio::fs::{metadata, read_to_string};
+    pub use tokio::time::sleep;
+
+    pub async fn lookup_host(host: &str) -> io::Result<Vec<SocketAddr>> {
+        Ok(tokio::net::lookup_host((host, 0)).await?.collect())
+    }
+}
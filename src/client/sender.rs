@@ -0,0 +1,138 @@
+use futures_util::future::BoxFuture;
+
+use crate::client::response::FcmResponse;
+use crate::message::Message;
+use crate::FcmClientError;
+
+/// Sends messages. Implemented by [FcmClient], and by [MockFcmSender]
+/// with the `test-utils` feature, so that code which sends messages can
+/// be tested without credentials.
+///
+/// ```rust
+/// use fcm::message::Message;
+/// use fcm::{FcmClientError, FcmSender};
+///
+/// async fn notify(sender: &dyn FcmSender, message: Message) -> Result<bool, FcmClientError> {
+///     Ok(sender.send(&message).await?.error().is_none())
+/// }
+/// ```
+pub trait FcmSender: Send + Sync {
+    fn send<'a>(&'a self, message: &'a Message) -> BoxFuture<'a, Result<FcmResponse, FcmClientError>>;
+}
+
+/// Futures of the fetch API are not `Send`, so the client is not a sender
+/// on wasm32.
+#[cfg(not(target_arch = "wasm32"))]
+impl FcmSender for crate::FcmClient {
+    fn send<'a>(&'a self, message: &'a Message) -> BoxFuture<'a, Result<FcmResponse, FcmClientError>> {
+        Box::pin(crate::FcmClient::send(self, message))
+    }
+}
+
+#[cfg(feature = "test-utils")]
+pub use self::mock::MockFcmSender;
+
+#[cfg(feature = "test-utils")]
+mod mock {
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    use serde_json::{Map, Value};
+
+    use super::*;
+
+    /// [FcmSender] which records sent messages and returns scripted
+    /// responses. Messages sent after the scripted responses run out
+    /// succeed.
+    ///
+    /// ```rust
+    /// use fcm::message::{Message, Target};
+    /// use fcm::response::FcmResponse;
+    /// use fcm::{FcmSender, MockFcmSender};
+    ///
+    /// # tokio_test(async {
+    /// let sender = MockFcmSender::new().respond(FcmResponse::mock(404, Default::default()));
+    ///
+    /// let message = Message::builder(Target::Token("token".to_string())).build();
+    /// let response = sender.send(&message).await.unwrap();
+    ///
+    /// assert_eq!(response.http_status_code(), 404);
+    /// assert_eq!(sender.sent()[0]["token"], "token");
+    /// # });
+    /// # fn tokio_test(f: impl std::future::Future<Output = ()>) {
+    /// #     tokio::runtime::Runtime::new().unwrap().block_on(f)
+    /// # }
+    /// ```
+    #[derive(Debug, Default)]
+    pub struct MockFcmSender {
+        responses: Mutex<VecDeque<Result<FcmResponse, FcmClientError>>>,
+        sent: Mutex<Vec<Value>>,
+    }
+
+    impl MockFcmSender {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Return `response` for the next message which has no scripted
+        /// response yet.
+        pub fn respond(self, response: FcmResponse) -> Self {
+            self.responses.lock().unwrap().push_back(Ok(response));
+            self
+        }
+
+        /// Fail the next message which has no scripted response yet.
+        pub fn fail(self, error: FcmClientError) -> Self {
+            self.responses.lock().unwrap().push_back(Err(error));
+            self
+        }
+
+        /// Messages sent so far, serialized like in the request body.
+        pub fn sent(&self) -> Vec<Value> {
+            self.sent.lock().unwrap().clone()
+        }
+
+        fn next_response(&self, message: &Message) -> Result<FcmResponse, FcmClientError> {
+            let value = serde_json::to_value(message)?;
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(value);
+            let sent_count = sent.len();
+
+            self.responses.lock().unwrap().pop_front().unwrap_or_else(|| {
+                let mut json = Map::new();
+                json.insert(
+                    "name".to_string(),
+                    format!("projects/mock/messages/{}", sent_count).into(),
+                );
+                Ok(FcmResponse::mock(200, json))
+            })
+        }
+    }
+
+    impl FcmSender for MockFcmSender {
+        fn send<'a>(&'a self, message: &'a Message) -> BoxFuture<'a, Result<FcmResponse, FcmClientError>> {
+            Box::pin(async move { self.next_response(message) })
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::message::Target;
+
+    #[tokio::test]
+    async fn test_mock_sender_returns_scripted_responses_in_order() {
+        let sender = MockFcmSender::new()
+            .respond(FcmResponse::mock(503, Default::default()))
+            .fail(FcmClientError::Closed);
+        let sender: &dyn FcmSender = &sender;
+        let message = Message::builder(Target::Topic("news".to_string())).build();
+
+        assert_eq!(sender.send(&message).await.unwrap().http_status_code(), 503);
+        assert!(matches!(sender.send(&message).await, Err(FcmClientError::Closed)));
+        let response = sender.send(&message).await.unwrap();
+        assert!(response.error().is_none());
+        assert_eq!(response.json()["name"], "projects/mock/messages/3");
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::client::response::{FcmResponse, FcmResponseError};
+use crate::client::rt::Instant;
+use crate::FcmClientError;
+
+/// Upper bounds of the latency histogram buckets in milliseconds. The last
+/// bucket counts sends slower than the largest bound.
+pub const LATENCY_BUCKETS_MS: [u64; 11] = [5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// Outcome labels, indexed by [outcome_index].
+const OUTCOMES: [&str; 11] = [
+    "SUCCESS",
+    "INVALID_ARGUMENT",
+    "UNREGISTERED",
+    "SENDER_ID_MISMATCH",
+    "QUOTA_EXCEEDED",
+    "UNAVAILABLE",
+    "INTERNAL",
+    "THIRD_PARTY_AUTH_ERROR",
+    "UNSPECIFIED_ERROR",
+    "UNKNOWN",
+    "CLIENT_ERROR",
+];
+
+/// What the client measures for telemetry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TelemetryMode {
+    /// Counters and a latency histogram.
+    #[default]
+    Full,
+    /// Only counters. Sends are not timed.
+    CountersOnly,
+}
+
+/// Settings of the aggregated send telemetry, see
+/// [crate::FcmClientBuilder::telemetry].
+///
+/// Sends are counted with atomic counters. Every `flush_interval` the
+/// counters are reset and their values are passed to the callback as a
+/// [TelemetrySnapshot], so the callback runs once per interval and not
+/// once per message.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TelemetryConfig {
+    #[serde(serialize_with = "crate::duration::serialize")]
+    flush_interval: Duration,
+    mode: TelemetryMode,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval: Duration::from_secs(10),
+            mode: TelemetryMode::default(),
+        }
+    }
+}
+
+impl TelemetryConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Minimum time between snapshots. Default is 10 seconds.
+    ///
+    /// Snapshots are flushed by sends, so a client which sends nothing
+    /// flushes only when [crate::FcmClient::flush_telemetry] or
+    /// [crate::FcmClient::close] is called.
+    pub fn flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    /// Default is [TelemetryMode::Full].
+    pub fn mode(mut self, mode: TelemetryMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// Latency of sends, including retries, in the buckets of
+/// [LATENCY_BUCKETS_MS].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// Number of sends per bucket. Has one more entry than
+    /// [LATENCY_BUCKETS_MS] for the sends slower than the largest bound.
+    pub counts: Vec<u64>,
+    /// Sum of the latencies.
+    pub sum: Duration,
+}
+
+/// Sends aggregated over one flush interval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TelemetrySnapshot {
+    /// Time since the previous snapshot.
+    pub period: Duration,
+    /// Number of sends by outcome: `SUCCESS`, an FCM error code or
+    /// `CLIENT_ERROR` for sends which failed without an FCM response.
+    /// Outcomes without sends are omitted.
+    pub outcomes: BTreeMap<&'static str, u64>,
+    /// Number of retries of all sends.
+    pub retries: u64,
+    /// `None` in [TelemetryMode::CountersOnly].
+    pub latency: Option<LatencyHistogram>,
+}
+
+impl TelemetrySnapshot {
+    pub fn sends(&self) -> u64 {
+        self.outcomes.values().sum()
+    }
+
+    pub fn failures(&self) -> u64 {
+        self.sends() - self.outcomes.get("SUCCESS").copied().unwrap_or(0)
+    }
+}
+
+type TelemetryCallback = dyn Fn(&TelemetrySnapshot) + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct TelemetrySink {
+    config: TelemetryConfig,
+    callback: Arc<TelemetryCallback>,
+}
+
+impl fmt::Debug for TelemetrySink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TelemetrySink").field("config", &self.config).finish()
+    }
+}
+
+impl TelemetrySink {
+    pub fn new(config: TelemetryConfig, callback: impl Fn(&TelemetrySnapshot) + Send + Sync + 'static) -> Self {
+        Self {
+            config,
+            callback: Arc::new(callback),
+        }
+    }
+
+    pub fn config(&self) -> &TelemetryConfig {
+        &self.config
+    }
+}
+
+/// Counters of a client.
+pub(crate) struct Telemetry {
+    sink: TelemetrySink,
+    outcomes: [AtomicU64; OUTCOMES.len()],
+    retries: AtomicU64,
+    latency_counts: [AtomicU64; LATENCY_BUCKETS_MS.len() + 1],
+    latency_sum_micros: AtomicU64,
+    last_flush: Mutex<Instant>,
+}
+
+impl Telemetry {
+    pub fn new(sink: TelemetrySink) -> Self {
+        Self {
+            sink,
+            outcomes: Default::default(),
+            retries: AtomicU64::new(0),
+            latency_counts: Default::default(),
+            latency_sum_micros: AtomicU64::new(0),
+            last_flush: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Start time of a send, if sends are timed.
+    pub fn start(&self) -> Option<Instant> {
+        (self.sink.config.mode == TelemetryMode::Full).then(Instant::now)
+    }
+
+    pub fn record(&self, started: Option<Instant>, result: &Result<FcmResponse, FcmClientError>) {
+        self.outcomes[outcome_index(result)].fetch_add(1, Ordering::Relaxed);
+        let retries = retry_count(result);
+        if retries > 0 {
+            self.retries.fetch_add(retries as u64, Ordering::Relaxed);
+        }
+        if let Some(started) = started {
+            let latency = started.elapsed();
+            let millis = latency.as_millis() as u64;
+            let bucket = LATENCY_BUCKETS_MS
+                .iter()
+                .position(|bound| millis <= *bound)
+                .unwrap_or(LATENCY_BUCKETS_MS.len());
+            self.latency_counts[bucket].fetch_add(1, Ordering::Relaxed);
+            self.latency_sum_micros
+                .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+        }
+        self.flush_if_due();
+    }
+
+    fn flush_if_due(&self) {
+        // Skip if another send is flushing.
+        let mut last_flush = match self.last_flush.try_lock() {
+            Ok(last_flush) => last_flush,
+            Err(_) => return,
+        };
+        if last_flush.elapsed() < self.sink.config.flush_interval {
+            return;
+        }
+        let snapshot = self.take_snapshot(&mut last_flush);
+        drop(last_flush);
+        (self.sink.callback)(&snapshot);
+    }
+
+    /// Flush the counters now, unless nothing was sent since the previous
+    /// snapshot.
+    pub fn flush(&self) {
+        let mut last_flush = self.last_flush.lock().unwrap();
+        let snapshot = self.take_snapshot(&mut last_flush);
+        drop(last_flush);
+        if snapshot.sends() > 0 {
+            (self.sink.callback)(&snapshot);
+        }
+    }
+
+    fn take_snapshot(&self, last_flush: &mut Instant) -> TelemetrySnapshot {
+        let now = Instant::now();
+        let period = now - *last_flush;
+        *last_flush = now;
+
+        let outcomes = OUTCOMES
+            .iter()
+            .zip(&self.outcomes)
+            .map(|(label, count)| (*label, count.swap(0, Ordering::Relaxed)))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        let latency = (self.sink.config.mode == TelemetryMode::Full).then(|| LatencyHistogram {
+            counts: self
+                .latency_counts
+                .iter()
+                .map(|count| count.swap(0, Ordering::Relaxed))
+                .collect(),
+            sum: Duration::from_micros(self.latency_sum_micros.swap(0, Ordering::Relaxed)),
+        });
+        TelemetrySnapshot {
+            period,
+            outcomes,
+            retries: self.retries.swap(0, Ordering::Relaxed),
+            latency,
+        }
+    }
+}
+
+/// Outcome label of a send: `SUCCESS`, an FCM error code or
+/// `CLIENT_ERROR`.
+pub(crate) fn outcome_label(result: &Result<FcmResponse, FcmClientError>) -> &'static str {
+    OUTCOMES[outcome_index(result)]
+}
+
+/// Number of retries of a send.
+pub(crate) fn retry_count(result: &Result<FcmResponse, FcmClientError>) -> usize {
+    match result {
+        Ok(response) => response.retry_history().len(),
+        Err(FcmClientError::RetriesFailed { retry_history, .. }) => retry_history.len(),
+        Err(_) => 0,
+    }
+}
+
+fn outcome_index(result: &Result<FcmResponse, FcmClientError>) -> usize {
+    let error = match result {
+        Ok(response) => response.error(),
+        Err(_) => return OUTCOMES.len() - 1,
+    };
+    match error {
+        None => 0,
+        Some(FcmResponseError::InvalidArgument) => 1,
+        Some(FcmResponseError::Unregistered) => 2,
+        Some(FcmResponseError::SenderIdMismatch) => 3,
+        Some(FcmResponseError::QuotaExceeded) => 4,
+        Some(FcmResponseError::Unavailable) => 5,
+        Some(FcmResponseError::Internal) => 6,
+        Some(FcmResponseError::ThirdPartyAuth) => 7,
+        Some(FcmResponseError::Unspecified) => 8,
+        Some(FcmResponseError::Unknown) => 9,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(http_status_code: u16) -> Result<FcmResponse, FcmClientError> {
+        let mut json = serde_json::Map::new();
+        json.insert("name".to_string(), "projects/p/messages/1".into());
+        Ok(FcmResponse::new(http_status_code, json, None))
+    }
+
+    #[test]
+    fn test_telemetry_aggregates_until_flush() {
+        let snapshots = Arc::new(Mutex::new(Vec::new()));
+        let sink_snapshots = snapshots.clone();
+        let config = TelemetryConfig::new()
+            .flush_interval(Duration::from_secs(3600))
+            .mode(TelemetryMode::CountersOnly);
+        let telemetry = Telemetry::new(TelemetrySink::new(config, move |snapshot| {
+            sink_snapshots.lock().unwrap().push(snapshot.clone())
+        }));
+
+        for status in [200, 200, 404] {
+            telemetry.record(telemetry.start(), &response(status));
+        }
+        telemetry.record(None, &Err(FcmClientError::Closed));
+        assert!(snapshots.lock().unwrap().is_empty());
+
+        telemetry.flush();
+        telemetry.flush();
+
+        let snapshots = snapshots.lock().unwrap();
+        assert_eq!(snapshots.len(), 1);
+        let snapshot = &snapshots[0];
+        assert_eq!(snapshot.sends(), 4);
+        assert_eq!(snapshot.failures(), 2);
+        assert_eq!(snapshot.outcomes["UNREGISTERED"], 1);
+        assert_eq!(snapshot.outcomes["CLIENT_ERROR"], 1);
+        assert_eq!(snapshot.latency, None);
+    }
+
+    #[test]
+    fn test_telemetry_full_mode_records_latency() {
+        let snapshots = Arc::new(Mutex::new(Vec::new()));
+        let sink_snapshots = snapshots.clone();
+        let telemetry = Telemetry::new(TelemetrySink::new(TelemetryConfig::new(), move |snapshot| {
+            sink_snapshots.lock().unwrap().push(snapshot.clone())
+        }));
+
+        telemetry.record(telemetry.start(), &response(200));
+        telemetry.flush();
+
+        let latency = snapshots.lock().unwrap()[0].latency.clone().unwrap();
+        assert_eq!(latency.counts.len(), LATENCY_BUCKETS_MS.len() + 1);
+        assert_eq!(latency.counts.iter().sum::<u64>(), 1);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[tokio::test]
+    async fn test_counters_only_mode_is_not_timed_with_metrics_observer() {
+        use crate::message::{Message, Target};
+        use crate::{FcmClient, MetricsObserver, MockServer, SendEvent};
+
+        #[derive(Default)]
+        struct Latencies(Mutex<Vec<Duration>>);
+
+        impl MetricsObserver for Arc<Latencies> {
+            fn on_send(&self, event: &SendEvent) {
+                self.0.lock().unwrap().push(event.latency);
+            }
+        }
+
+        let server = MockServer::start().await.unwrap();
+        let latencies = Arc::new(Latencies::default());
+        let client = FcmClient::builder()
+            .endpoint(server.url())
+            .telemetry(TelemetryConfig::new().mode(TelemetryMode::CountersOnly), |_| {})
+            .metrics_observer(latencies.clone())
+            .build()
+            .await
+            .unwrap();
+
+        let message = Message::builder(Target::Token("token".to_string())).build();
+        client.send(&message).await.unwrap();
+
+        let telemetry = client.telemetry.as_ref().unwrap();
+        assert_eq!(telemetry.outcomes[0].load(Ordering::Relaxed), 1);
+        assert!(telemetry
+            .latency_counts
+            .iter()
+            .all(|count| count.load(Ordering::Relaxed) == 0));
+        assert_eq!(latencies.0.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,244 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::Notify;
+
+use crate::client::metrics::ObserverHook;
+use crate::client::response::{FcmResponse, FcmResponseError};
+use crate::client::rt::Instant;
+use crate::FcmClientError;
+
+/// Throttling responses within this time after a decrease were most likely
+/// caused by requests sent before it, so they do not decrease the limit
+/// again.
+const DECREASE_COOLDOWN: Duration = Duration::from_secs(1);
+
+/// Adaptive limit for the number of concurrent send attempts, set with
+/// [crate::FcmClientBuilder::adaptive_throttling].
+///
+/// The limit starts at `max_concurrency`. It is halved when FCM responds
+/// with HTTP 429 or `QUOTA_EXCEEDED` and raised by one after every quiet
+/// period without throttling, following
+/// <https://firebase.google.com/docs/cloud-messaging/scale-fcm#throttling-and-scaling>.
+/// The limit applies to all sends of the client together.
+///
+/// Only concurrency is limited. The crate has no rate limiter, so a low
+/// latency still allows many requests per second.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AdaptiveThrottling {
+    max_concurrency: usize,
+    min_concurrency: usize,
+    #[serde(serialize_with = "crate::duration::serialize")]
+    quiet_period: Duration,
+}
+
+impl AdaptiveThrottling {
+    pub fn new(max_concurrency: usize) -> Self {
+        let max_concurrency = max_concurrency.max(1);
+        Self {
+            max_concurrency,
+            min_concurrency: 1,
+            quiet_period: Duration::from_secs(10),
+        }
+    }
+
+    /// Lowest limit, at least 1. Default is 1.
+    pub fn min_concurrency(mut self, min_concurrency: usize) -> Self {
+        self.min_concurrency = min_concurrency.clamp(1, self.max_concurrency);
+        self
+    }
+
+    /// Time without throttling after which the limit is raised by one.
+    /// Default is 10 seconds.
+    pub fn quiet_period(mut self, quiet_period: Duration) -> Self {
+        self.quiet_period = quiet_period;
+        self
+    }
+
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+}
+
+#[derive(Debug)]
+struct ThrottleState {
+    limit: usize,
+    in_use: usize,
+    /// Time of the last change of the limit, or of the last throttling
+    /// response if it did not change the limit.
+    changed_at: Instant,
+    decreased_at: Option<Instant>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Throttle {
+    config: AdaptiveThrottling,
+    state: Mutex<ThrottleState>,
+    released: Notify,
+    observer: Option<ObserverHook>,
+}
+
+impl Throttle {
+    pub fn new(config: AdaptiveThrottling, observer: Option<ObserverHook>) -> Self {
+        Self {
+            state: Mutex::new(ThrottleState {
+                limit: config.max_concurrency,
+                in_use: 0,
+                changed_at: Instant::now(),
+                decreased_at: None,
+            }),
+            config,
+            released: Notify::new(),
+            observer,
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.state.lock().unwrap().limit
+    }
+
+    /// Wait until an attempt can be sent under the current limit.
+    pub async fn acquire(&self) -> ThrottlePermit<'_> {
+        loop {
+            let released = self.released.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.in_use < state.limit {
+                    state.in_use += 1;
+                    return ThrottlePermit { throttle: self };
+                }
+            }
+            released.await;
+        }
+    }
+
+    /// Update the limit with the result of an attempt.
+    pub fn observe(&self, result: &Result<FcmResponse, FcmClientError>) {
+        let throttled = matches!(result, Ok(response) if is_throttled(response));
+        let now = Instant::now();
+        let changed_limit = {
+            let mut state = self.state.lock().unwrap();
+            let previous = state.limit;
+            if throttled {
+                if state.decreased_at.is_none_or(|at| now - at >= DECREASE_COOLDOWN) {
+                    state.limit = (state.limit / 2).max(self.config.min_concurrency);
+                    state.decreased_at = Some(now);
+                }
+                state.changed_at = now;
+            } else if state.limit < self.config.max_concurrency && now - state.changed_at >= self.config.quiet_period {
+                state.limit += 1;
+                state.changed_at = now;
+            }
+            (state.limit != previous).then_some(state.limit)
+        };
+
+        if let Some(limit) = changed_limit {
+            self.released.notify_waiters();
+            if let Some(observer) = &self.observer {
+                observer.0.on_concurrency_change(limit);
+            }
+        }
+    }
+}
+
+fn is_throttled(response: &FcmResponse) -> bool {
+    response.http_status_code() == 429 || response.error() == Some(FcmResponseError::QuotaExceeded)
+}
+
+/// Slot of one attempt, released when dropped.
+pub(crate) struct ThrottlePermit<'a> {
+    throttle: &'a Throttle,
+}
+
+impl Drop for ThrottlePermit<'_> {
+    fn drop(&mut self) {
+        self.throttle.state.lock().unwrap().in_use -= 1;
+        self.throttle.released.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(http_status_code: u16) -> Result<FcmResponse, FcmClientError> {
+        Ok(FcmResponse::new(http_status_code, Default::default(), None))
+    }
+
+    #[test]
+    fn test_limit_halves_on_throttling_and_recovers() {
+        let throttle = Throttle::new(
+            AdaptiveThrottling::new(8)
+                .min_concurrency(3)
+                .quiet_period(Duration::ZERO),
+            None,
+        );
+
+        throttle.observe(&response(429));
+        assert_eq!(throttle.limit(), 4);
+        // Within the cooldown the limit is not decreased again.
+        throttle.observe(&response(429));
+        assert_eq!(throttle.limit(), 4);
+
+        throttle.observe(&response(200));
+        throttle.observe(&response(200));
+        assert_eq!(throttle.limit(), 6);
+        for _ in 0..5 {
+            throttle.observe(&response(200));
+        }
+        assert_eq!(throttle.limit(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_free_slot() {
+        let throttle = Throttle::new(AdaptiveThrottling::new(1), None);
+        let permit = throttle.acquire().await;
+
+        let second = throttle.acquire();
+        futures_util::pin_mut!(second);
+        assert!(tokio::time::timeout(Duration::from_millis(20), second.as_mut())
+            .await
+            .is_err());
+        drop(permit);
+        tokio::time::timeout(Duration::from_secs(1), second).await.unwrap();
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[tokio::test]
+    async fn test_client_reports_concurrency_changes() {
+        use std::sync::Arc;
+
+        use crate::message::{Message, Target};
+        use crate::{FcmClient, MetricsObserver, MockReply, MockServer, RetryPolicy, SendEvent};
+
+        #[derive(Default)]
+        struct Limits(Mutex<Vec<usize>>);
+
+        impl MetricsObserver for Arc<Limits> {
+            fn on_send(&self, _event: &SendEvent) {}
+
+            fn on_concurrency_change(&self, concurrency_limit: usize) {
+                self.0.lock().unwrap().push(concurrency_limit);
+            }
+        }
+
+        let server = MockServer::start().await.unwrap();
+        let limits = Arc::new(Limits::default());
+        let client = FcmClient::builder()
+            .endpoint(server.url())
+            .retry_policy(RetryPolicy::new(0))
+            .adaptive_throttling(AdaptiveThrottling::new(10))
+            .metrics_observer(limits.clone())
+            .build()
+            .await
+            .unwrap();
+        server.reply(MockReply::QuotaExceeded { retry_after: None });
+
+        let message = Message::builder(Target::Token("token".to_string())).build();
+        client.send(&message).await.unwrap();
+
+        assert_eq!(client.concurrency_limit(), Some(5));
+        assert_eq!(*limits.0.lock().unwrap(), vec![5]);
+    }
+}
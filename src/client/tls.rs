@@ -0,0 +1,378 @@
+//! TLS settings which apply to both FCM requests and OAuth token requests.
+
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use hyper::client::HttpConnector;
+use hyper_rustls::HttpsConnector;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use yup_oauth2::authenticator::{DefaultHyperClient, HyperClientBuilder};
+
+use crate::client::config_error::ConfigError;
+use crate::client::oauth::OauthError;
+
+/// Root certificate which is trusted in addition to the system roots, set
+/// with [crate::FcmClientBuilder::add_root_certificate]. For example the
+/// certificate authority of a TLS-inspecting proxy.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RootCertificate {
+    der: Vec<u8>,
+}
+
+impl RootCertificate {
+    pub fn from_der(der: impl Into<Vec<u8>>) -> Self {
+        Self { der: der.into() }
+    }
+
+    /// Read the first certificate of a PEM file.
+    pub fn from_pem(pem: &[u8]) -> Result<Self, ConfigError> {
+        Self::from_pem_bundle(pem)?
+            .into_iter()
+            .next()
+            .ok_or(ConfigError::MissingCertificate)
+    }
+
+    /// Read every certificate of a PEM file, such as a CA bundle.
+    pub fn from_pem_bundle(mut pem: &[u8]) -> Result<Vec<Self>, ConfigError> {
+        rustls_pemfile::certs(&mut pem)
+            .map(|certificate| match certificate {
+                Ok(certificate) => Ok(Self::from_der(certificate.as_ref())),
+                Err(error) => Err(ConfigError::UnreadableCertificate(error)),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for RootCertificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RootCertificate")
+            .field("len", &self.der.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TlsConfig {
+    pub root_certificates: Vec<RootCertificate>,
+    pub accept_invalid_certs: bool,
+}
+
+impl TlsConfig {
+    pub(crate) fn apply(&self, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder, ConfigError> {
+        // Not every TLS backend of reqwest checks the certificates, so
+        // invalid ones fail here like they would for OAuth requests.
+        self.root_store()?;
+        let mut builder = builder.danger_accept_invalid_certs(self.accept_invalid_certs);
+        for certificate in &self.root_certificates {
+            let certificate =
+                reqwest::Certificate::from_der(&certificate.der).map_err(ConfigError::RejectedCertificate)?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        Ok(builder)
+    }
+
+    /// HTTP client for OAuth token requests. Without custom settings this
+    /// is the default client of `yup-oauth2`.
+    pub(crate) fn oauth_http_client(&self) -> Result<hyper::Client<HttpsConnector<HttpConnector>>, OauthError> {
+        if self.root_certificates.is_empty() && !self.accept_invalid_certs {
+            return DefaultHyperClient.build_hyper_client().map_err(OauthError::Oauth);
+        }
+
+        let config = if self.accept_invalid_certs {
+            let provider = rustls::crypto::ring::default_provider();
+            ClientConfig::builder()
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)))
+                .with_no_client_auth()
+        } else {
+            let mut roots = self.root_store().map_err(|error| {
+                OauthError::AuthenticatorCreatingFailed(io::Error::new(io::ErrorKind::InvalidData, error))
+            })?;
+            let native_roots =
+                rustls_native_certs::load_native_certs().map_err(OauthError::AuthenticatorCreatingFailed)?;
+            roots.add_parsable_certificates(native_roots);
+            ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth()
+        };
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(config)
+            .https_or_http()
+            .enable_http1()
+            .enable_http2()
+            .build();
+        Ok(hyper::Client::builder()
+            .pool_max_idle_per_host(0)
+            .build::<_, hyper::Body>(connector))
+    }
+
+    /// Store of the custom root certificates. Unlike the system roots,
+    /// which are skipped if they can not be parsed, every custom root must
+    /// be valid.
+    fn root_store(&self) -> Result<RootCertStore, ConfigError> {
+        let mut roots = RootCertStore::empty();
+        let (_, ignored) = roots.add_parsable_certificates(
+            self.root_certificates
+                .iter()
+                .map(|certificate| CertificateDer::from(certificate.der.clone())),
+        );
+        if ignored > 0 {
+            return Err(ConfigError::InvalidCertificate(format!(
+                "{} of {} root certificates are not valid",
+                ignored,
+                self.root_certificates.len()
+            )));
+        }
+        Ok(roots)
+    }
+}
+
+/// Verifier for [crate::FcmClientBuilder::danger_accept_invalid_certs],
+/// which accepts any certificate but still checks handshake signatures.
+#[derive(Debug)]
+struct AcceptAnyCertificate(CryptoProvider);
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "test-utils")]
+    use crate::client::test_helpers::{service_account_key, TEST_PRIVATE_KEY};
+
+    #[test]
+    fn test_root_certificate_from_pem_bundle() {
+        let pem = b"-----BEGIN CERTIFICATE-----\nAQID\n-----END CERTIFICATE-----\n\
+                    -----BEGIN CERTIFICATE-----\nBAUG\n-----END CERTIFICATE-----\n";
+
+        let certificates = RootCertificate::from_pem_bundle(pem).unwrap();
+        assert_eq!(
+            certificates,
+            vec![
+                RootCertificate::from_der(vec![1, 2, 3]),
+                RootCertificate::from_der(vec![4, 5, 6])
+            ]
+        );
+        assert!(matches!(
+            RootCertificate::from_pem(b"not a certificate"),
+            Err(ConfigError::MissingCertificate)
+        ));
+        let error =
+            RootCertificate::from_pem(b"-----BEGIN CERTIFICATE-----\n!!!\n-----END CERTIFICATE-----\n").unwrap_err();
+        assert!(matches!(error, ConfigError::UnreadableCertificate(_)));
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn test_invalid_root_certificate_is_rejected() {
+        let tls = TlsConfig {
+            root_certificates: vec![RootCertificate::from_der(vec![1, 2, 3])],
+            accept_invalid_certs: false,
+        };
+
+        assert!(matches!(
+            tls.apply(reqwest::ClientBuilder::new()),
+            Err(ConfigError::InvalidCertificate(_))
+        ));
+        assert!(matches!(
+            tls.oauth_http_client(),
+            Err(OauthError::AuthenticatorCreatingFailed(_))
+        ));
+    }
+
+    /// Certificate authority of [SERVER_CERT], valid until 2126.
+    #[cfg(feature = "test-utils")]
+    const TEST_CA: &str = "\
+-----BEGIN CERTIFICATE-----\n\
+MIIBkzCCATmgAwIBAgIUeySAO6TZW9FV5+HomzQvA8pot0kwCgYIKoZIzj0EAwIw\n\
+FjEUMBIGA1UEAwwLZmNtIHRlc3QgQ0EwIBcNMjYxMDE2MDMwMDE0WhgPMjEyNjA5\n\
+MjIwMzAwMTRaMBYxFDASBgNVBAMMC2ZjbSB0ZXN0IENBMFkwEwYHKoZIzj0CAQYI\n\
+KoZIzj0DAQcDQgAE/JbptTSW/t2ydKUTQqX02r5weLHWymGw+6Xyhm6BIrihr+dh\n\
+uw7Pi5E1gzrncB/9Cc5nGfK8iBVsiyHnpAOnd6NjMGEwHQYDVR0OBBYEFJh/Sq+k\n\
+lgurkOx0i1h1gTOOg/cMMB8GA1UdIwQYMBaAFJh/Sq+klgurkOx0i1h1gTOOg/cM\n\
+MA8GA1UdEwEB/wQFMAMBAf8wDgYDVR0PAQH/BAQDAgIEMAoGCCqGSM49BAMCA0gA\n\
+MEUCIQD6Z0xAptLbbdJzv4KTINMpAatIiJzjoGUQpJZgxTVvswIgHZ2p200tQ95m\n\
+hS4fC3QZqZrc7VaU7BHj9kDhB+8nAak=\n\
+-----END CERTIFICATE-----\n";
+
+    /// Certificate of `127.0.0.1` and `localhost` for [TEST_PRIVATE_KEY].
+    #[cfg(feature = "test-utils")]
+    const SERVER_CERT: &str = "\
+-----BEGIN CERTIFICATE-----\n\
+MIICiTCCAi+gAwIBAgIUGYC9VP4V2grCdeQJkkQmfMVy0oEwCgYIKoZIzj0EAwIw\n\
+FjEUMBIGA1UEAwwLZmNtIHRlc3QgQ0EwIBcNMjYxMDE2MDMwMDE0WhgPMjEyNjA5\n\
+MjIwMzAwMTRaMBQxEjAQBgNVBAMMCTEyNy4wLjAuMTCCASIwDQYJKoZIhvcNAQEB\n\
+BQADggEPADCCAQoCggEBAMSnIbyby+mJsjrGECIXQ2Eo6TSFJQPDRHSWuQCxcKMx\n\
+k7nUmCxCxP4BLiEkXMbe+F2OP/0KvbZvkIItThj01iwpNoA4k+BGYlyyXP0AnXEz\n\
+8FNk8p/VtTd0YiRl/utTyWAjH3k7nWnXY98CzU1G/IKYS2Vr0TN8OSeMvsMJ+UN1\n\
+OnotiFiXnxCSVfiV1taf0KnADDQUGbZ+kwB4VLUdjUJjHZB4nb6K1tNQPutVWxOM\n\
+vr+nLgP2ZBVy0bEn+q6Wuj4/ctS+BpSjL4ganLR+12/c97HOfzl27savCFZOuZu3\n\
+zix0p61CPGfFAkS2bBG/SbGq1wJBJk0jFZOKCor9GtMCAwEAAaOBjzCBjDAJBgNV\n\
+HRMEAjAAMA4GA1UdDwEB/wQEAwIFoDATBgNVHSUEDDAKBggrBgEFBQcDATAaBgNV\n\
+HREEEzARhwR/AAABgglsb2NhbGhvc3QwHQYDVR0OBBYEFMVq1lvoHEPLInNhK/Hb\n\
+OuulV5klMB8GA1UdIwQYMBaAFJh/Sq+klgurkOx0i1h1gTOOg/cMMAoGCCqGSM49\n\
+BAMCA0gAMEUCIA0NiSaLXrdL//dRDAISJtrG4khu/UJmcGpwIt9RGaLJAiEA7ZRH\n\
+VIIPsKuI3kMSkXGM/3k1S/dPKxCpzcrzWcIS3uM=\n\
+-----END CERTIFICATE-----\n";
+
+    /// HTTPS server with [SERVER_CERT] which answers OAuth token requests
+    /// and FCM send requests. Returns its port and the paths of the requests.
+    #[cfg(feature = "test-utils")]
+    async fn start_https_server() -> (u16, Arc<std::sync::Mutex<Vec<String>>>) {
+        use tokio::io::AsyncWriteExt;
+
+        let certs = rustls_pemfile::certs(&mut SERVER_CERT.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let key = rustls_pemfile::private_key(&mut TEST_PRIVATE_KEY.as_bytes())
+            .unwrap()
+            .unwrap();
+        let config = rustls::ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let paths = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let server_paths = paths.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let acceptor = acceptor.clone();
+                let paths = server_paths.clone();
+                tokio::spawn(async move {
+                    let mut stream = match acceptor.accept(stream).await {
+                        Ok(stream) => stream,
+                        Err(_) => return,
+                    };
+                    let path = match crate::client::mock_server::read_request(&mut stream).await {
+                        Ok((path, _)) => path,
+                        Err(_) => return,
+                    };
+                    let body = match path.as_str() {
+                        "/token" => r#"{"access_token":"ya29.tls","expires_in":3600,"token_type":"Bearer"}"#,
+                        _ => r#"{"name":"projects/tls-project/messages/1"}"#,
+                    };
+                    paths.lock().unwrap().push(path);
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+        (port, paths)
+    }
+
+    /// Sends FCM requests to the test server instead of FCM.
+    #[cfg(feature = "test-utils")]
+    struct Redirect(u16);
+
+    #[cfg(feature = "test-utils")]
+    impl crate::Interceptor for Redirect {
+        fn before_send(&self, request: &mut reqwest::Request) -> Result<(), crate::InterceptorError> {
+            request.url_mut().set_host(Some("127.0.0.1"))?;
+            request.url_mut().set_port(Some(self.0)).ok();
+            Ok(())
+        }
+    }
+
+    /// Send a message with a client of `builder` whose OAuth token requests
+    /// and FCM requests both go to the test server.
+    #[cfg(feature = "test-utils")]
+    async fn send_over_https(
+        builder: crate::FcmClientBuilder,
+    ) -> (Result<crate::response::FcmResponse, crate::FcmClientError>, Vec<String>) {
+        use crate::message::{Message, Target};
+
+        let (port, paths) = start_https_server().await;
+        let key = service_account_key("tls-project", &format!("https://127.0.0.1:{}/token", port));
+        let result = async {
+            let client = builder
+                .service_account_key_json_string(key)
+                .interceptor(Redirect(port))
+                .retry_policy(crate::RetryPolicy::new(0))
+                .build()
+                .await?;
+            client
+                .send(Message::builder(Target::Token("token".to_string())).build())
+                .await
+        }
+        .await;
+        let paths = paths.lock().unwrap().clone();
+        (result, paths)
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[tokio::test]
+    async fn test_root_certificate_applies_to_oauth_and_fcm_requests() {
+        let builder =
+            crate::FcmClient::builder().add_root_certificate(RootCertificate::from_pem(TEST_CA.as_bytes()).unwrap());
+
+        let (result, paths) = send_over_https(builder).await;
+
+        assert_eq!(result.unwrap().http_status_code(), 200);
+        assert_eq!(paths, vec!["/token", "/v1/projects/tls-project/messages:send"]);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[tokio::test]
+    async fn test_accept_invalid_certs_applies_to_oauth_and_fcm_requests() {
+        let builder = crate::FcmClient::builder().danger_accept_invalid_certs(true);
+
+        let (result, paths) = send_over_https(builder).await;
+
+        assert_eq!(result.unwrap().http_status_code(), 200);
+        assert_eq!(paths, vec!["/token", "/v1/projects/tls-project/messages:send"]);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[tokio::test]
+    async fn test_unknown_certificate_authority_is_rejected() {
+        let (result, paths) = send_over_https(crate::FcmClient::builder()).await;
+
+        assert!(matches!(result, Err(crate::FcmClientError::Oauth(_))));
+        assert!(paths.is_empty());
+    }
+}
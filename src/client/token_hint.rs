@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::client::response::{FcmResponse, FcmResponseError};
+use crate::message::Target;
+use crate::FcmClientError;
+
+/// Upper limit for the number of throttled tokens which are tracked at the
+/// same time. Tracking is reset when the limit is reached.
+const MAX_TRACKED_TOKENS: usize = 10_000;
+
+/// Advisory event about a registration token, derived from FCM responses
+/// to messages sent to that token.
+///
+/// FCM does not tell when a valid token has gone stale, but repeated
+/// throttling of a single device is a common sign of it. Apps can react to
+/// these events by asking the device to refresh and re-register its token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenHint {
+    /// Messages to `token` were throttled `count` times in a row without
+    /// a successful delivery in between.
+    RepeatedThrottling { token: String, count: u32 },
+    /// FCM reported that `token` is no longer registered. The token should
+    /// be removed.
+    Unregistered { token: String },
+}
+
+type TokenHintCallback = dyn Fn(&TokenHint) + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct TokenHintSink {
+    threshold: u32,
+    callback: Arc<TokenHintCallback>,
+    throttled: Arc<Mutex<HashMap<String, u32>>>,
+}
+
+impl fmt::Debug for TokenHintSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenHintSink")
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
+
+impl TokenHintSink {
+    pub fn new(threshold: u32, callback: impl Fn(&TokenHint) + Send + Sync + 'static) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            callback: Arc::new(callback),
+            throttled: Default::default(),
+        }
+    }
+
+    /// Update the throttling counters with the result of a single attempt
+    /// and emit hints.
+    pub fn observe(&self, target: &Target, result: &Result<FcmResponse, FcmClientError>) {
+        let token = match target {
+            Target::Token(token) => token,
+            _ => return,
+        };
+        let response = match result {
+            Ok(response) => response,
+            Err(_) => return,
+        };
+
+        let hint = match response.error() {
+            None => {
+                self.throttled.lock().unwrap().remove(token);
+                None
+            }
+            Some(FcmResponseError::QuotaExceeded) => {
+                let mut throttled = self.throttled.lock().unwrap();
+                if throttled.len() >= MAX_TRACKED_TOKENS && !throttled.contains_key(token) {
+                    throttled.clear();
+                }
+                let count = throttled.entry(token.clone()).or_default();
+                *count += 1;
+                (*count == self.threshold).then(|| TokenHint::RepeatedThrottling {
+                    token: token.clone(),
+                    count: *count,
+                })
+            }
+            Some(FcmResponseError::Unregistered) => {
+                self.throttled.lock().unwrap().remove(token);
+                Some(TokenHint::Unregistered { token: token.clone() })
+            }
+            Some(_) => None,
+        };
+
+        // Call outside of the lock, the callback may send messages.
+        if let Some(hint) = hint {
+            (self.callback)(&hint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(http_status_code: u16) -> Result<FcmResponse, FcmClientError> {
+        let json = serde_json::json!({ "name": "projects/p/messages/1" });
+        let json = if http_status_code == 200 {
+            json.as_object().unwrap().clone()
+        } else {
+            Default::default()
+        };
+        Ok(FcmResponse::new(http_status_code, json, None))
+    }
+
+    #[test]
+    fn test_repeated_throttling_emits_hint_once() {
+        let hints = Arc::new(Mutex::new(Vec::new()));
+        let sink = {
+            let hints = hints.clone();
+            TokenHintSink::new(2, move |hint| hints.lock().unwrap().push(hint.clone()))
+        };
+        let target = Target::Token("token".to_string());
+
+        sink.observe(&target, &response(429));
+        sink.observe(&target, &response(200));
+        sink.observe(&target, &response(429));
+        sink.observe(&target, &response(429));
+        sink.observe(&target, &response(429));
+        sink.observe(&target, &response(404));
+
+        assert_eq!(
+            vec![
+                TokenHint::RepeatedThrottling {
+                    token: "token".to_string(),
+                    count: 2
+                },
+                TokenHint::Unregistered {
+                    token: "token".to_string()
+                },
+            ],
+            *hints.lock().unwrap()
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Screening registration tokens with `validate_only` sends.
+
+use crate::client::response::FcmResponseError;
+use crate::message::{Message, Target};
+use crate::{FcmClient, FcmClientError};
+
+/// Result of [FcmClient::verify_token].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenStatus {
+    /// FCM accepted the token.
+    Valid,
+    /// The app instance was unregistered, so the token should be removed.
+    Unregistered,
+    /// The token is malformed or belongs to another Firebase project.
+    Invalid,
+    /// FCM failed with an error which says nothing about the token, for
+    /// example [FcmResponseError::QuotaExceeded]. Check the token again
+    /// later.
+    Unknown(FcmResponseError),
+}
+
+impl TokenStatus {
+    fn from_error(error: Option<FcmResponseError>) -> Self {
+        match error {
+            None => TokenStatus::Valid,
+            Some(FcmResponseError::Unregistered) => TokenStatus::Unregistered,
+            Some(FcmResponseError::InvalidArgument | FcmResponseError::SenderIdMismatch) => TokenStatus::Invalid,
+            Some(error) => TokenStatus::Unknown(error),
+        }
+    }
+}
+
+impl FcmClient {
+    /// Check a registration token with a `validate_only` send of a
+    /// message without payload, for example at sign-up. Nothing is
+    /// delivered to the device.
+    ///
+    /// Transport errors and errors of the client, such as a closed client,
+    /// are returned as `Err`.
+    pub async fn verify_token(&self, token: impl Into<String>) -> Result<TokenStatus, FcmClientError> {
+        let message = Message::builder(Target::Token(token.into())).build();
+        let params = super::SendParams {
+            validate_only: true,
+            ..self.send_params()
+        };
+        let response = self.send_with_retries(&message, params).await?;
+        Ok(TokenStatus::from_error(response.error()))
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::{MockReply, MockServer, RetryPolicy};
+
+    #[tokio::test]
+    async fn test_verify_token_maps_fcm_errors() {
+        let server = MockServer::start().await.unwrap();
+        let client = FcmClient::builder()
+            .endpoint(server.url())
+            .retry_policy(RetryPolicy::new(0))
+            .build()
+            .await
+            .unwrap();
+        server.replies([
+            MockReply::Success,
+            MockReply::Unregistered,
+            MockReply::Custom {
+                http_status_code: 400,
+                body: serde_json::json!({ "error": { "status": "INVALID_ARGUMENT" } }),
+            },
+            MockReply::Internal,
+        ]);
+
+        assert_eq!(TokenStatus::Valid, client.verify_token("a").await.unwrap());
+        assert_eq!(TokenStatus::Unregistered, client.verify_token("b").await.unwrap());
+        assert_eq!(TokenStatus::Invalid, client.verify_token("c").await.unwrap());
+        assert_eq!(
+            TokenStatus::Unknown(FcmResponseError::Internal),
+            client.verify_token("d").await.unwrap()
+        );
+        let requests = server.requests();
+        assert_eq!(requests[0]["validate_only"], true);
+        assert_eq!(requests[0]["message"], serde_json::json!({ "token": "a" }));
+    }
+}
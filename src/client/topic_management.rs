@@ -0,0 +1,158 @@
+//! Topic subscription management with the Instance ID API.
+//!
+//! <https://developers.google.com/instance-id/reference/server#manage_relationship_maps_for_multiple_app_instances>
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{FcmClient, FcmClientError};
+
+/// Maximum number of registration tokens in a single topic management request.
+pub const MAX_TOPIC_MANAGEMENT_TOKENS: usize = 1000;
+
+const IID_BATCH_ADD_URL: &str = "https://iid.googleapis.com/iid/v1:batchAdd";
+const IID_BATCH_REMOVE_URL: &str = "https://iid.googleapis.com/iid/v1:batchRemove";
+
+#[derive(thiserror::Error, Debug)]
+pub enum TopicManagementError {
+    #[error("At least one registration token is required")]
+    NoTokens,
+    #[error("Request contains {count} registration tokens, maximum is {MAX_TOPIC_MANAGEMENT_TOKENS}")]
+    TooManyTokens { count: usize },
+    #[error("Instance ID response does not contain a result for every token")]
+    MissingResults,
+}
+
+/// Result of subscribing or unsubscribing a single registration token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicManagementResult {
+    pub token: String,
+    /// Error reported by the Instance ID API, for example `NOT_FOUND` or
+    /// `INVALID_ARGUMENT`. `None` if the operation succeeded.
+    pub error: Option<String>,
+}
+
+/// Per token results of [FcmClient::subscribe_to_topic] and
+/// [FcmClient::unsubscribe_from_topic] in the same order as the tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicManagementResponse {
+    results: Vec<TopicManagementResult>,
+}
+
+impl TopicManagementResponse {
+    pub fn results(&self) -> &[TopicManagementResult] {
+        &self.results
+    }
+
+    pub fn success_count(&self) -> usize {
+        self.results.iter().filter(|result| result.error.is_none()).count()
+    }
+
+    pub fn failure_count(&self) -> usize {
+        self.results.len() - self.success_count()
+    }
+
+    fn parse(tokens: &[impl AsRef<str>], body: &[u8]) -> Result<Self, FcmClientError> {
+        #[derive(Deserialize)]
+        struct Body {
+            #[serde(default)]
+            results: Vec<ResultBody>,
+        }
+        #[derive(Deserialize)]
+        struct ResultBody {
+            error: Option<String>,
+        }
+
+        let body: Body = serde_json::from_slice(body)?;
+        if body.results.len() != tokens.len() {
+            return Err(TopicManagementError::MissingResults.into());
+        }
+        let results = tokens
+            .iter()
+            .zip(body.results)
+            .map(|(token, result)| TopicManagementResult {
+                token: token.as_ref().to_string(),
+                error: result.error,
+            })
+            .collect();
+        Ok(Self { results })
+    }
+}
+
+fn topic_path(topic: &str) -> String {
+    if topic.starts_with("/topics/") {
+        topic.to_string()
+    } else {
+        format!("/topics/{}", topic)
+    }
+}
+
+impl FcmClient {
+    /// Subscribe at most [MAX_TOPIC_MANAGEMENT_TOKENS] registration tokens
+    /// to `topic`.
+    pub async fn subscribe_to_topic(
+        &self,
+        topic: &str,
+        tokens: &[impl AsRef<str>],
+    ) -> Result<TopicManagementResponse, FcmClientError> {
+        self.manage_topic(IID_BATCH_ADD_URL, topic, tokens).await
+    }
+
+    /// Unsubscribe at most [MAX_TOPIC_MANAGEMENT_TOKENS] registration
+    /// tokens from `topic`.
+    pub async fn unsubscribe_from_topic(
+        &self,
+        topic: &str,
+        tokens: &[impl AsRef<str>],
+    ) -> Result<TopicManagementResponse, FcmClientError> {
+        self.manage_topic(IID_BATCH_REMOVE_URL, topic, tokens).await
+    }
+
+    async fn manage_topic(
+        &self,
+        url: &str,
+        topic: &str,
+        tokens: &[impl AsRef<str>],
+    ) -> Result<TopicManagementResponse, FcmClientError> {
+        if tokens.is_empty() {
+            return Err(TopicManagementError::NoTokens.into());
+        }
+        if tokens.len() > MAX_TOPIC_MANAGEMENT_TOKENS {
+            return Err(TopicManagementError::TooManyTokens { count: tokens.len() }.into());
+        }
+        let body = json!({
+            "to": topic_path(topic),
+            "registration_tokens": tokens.iter().map(AsRef::as_ref).collect::<Vec<_>>(),
+        });
+        let response = self.management_request(self.http_client.post(url).json(&body)).await?;
+        TopicManagementResponse::parse(tokens, &response.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_path() {
+        assert_eq!("/topics/news", topic_path("news"));
+        assert_eq!("/topics/news", topic_path("/topics/news"));
+    }
+
+    #[test]
+    fn test_parse_per_token_results() {
+        let body = br#"{"results":[{},{"error":"NOT_FOUND"},{}]}"#;
+        let response = TopicManagementResponse::parse(&["a", "b", "c"], body).unwrap();
+
+        assert_eq!(2, response.success_count());
+        assert_eq!(1, response.failure_count());
+        assert_eq!(
+            TopicManagementResult {
+                token: "b".to_string(),
+                error: Some("NOT_FOUND".to_string()),
+            },
+            response.results()[1]
+        );
+        assert!(TopicManagementResponse::parse(&["a"], body).is_err());
+    }
+}
@@ -0,0 +1,260 @@
+//! Spans for the `tracing` and `opentelemetry` features. Without the
+//! features the functions only run the futures.
+
+use std::future::Future;
+
+#[cfg(feature = "tracing")]
+use crate::client::logging::{error_chain, mask_token};
+use crate::client::response::FcmResponse;
+use crate::message::Target;
+use crate::FcmClientError;
+
+#[cfg(feature = "tracing")]
+fn target_fields(target: &Target) -> (&'static str, String) {
+    match target {
+        Target::Token(token) => ("token", mask_token(token)),
+        Target::Topic(topic) => ("topic", topic.clone()),
+        Target::Condition(condition) => ("condition", condition.clone()),
+    }
+}
+
+/// Run `send` in an `fcm.send` span which records the target, project ID,
+/// message ID, HTTP status, number of attempts and latency.
+#[cfg(feature = "tracing")]
+pub(crate) async fn send<F>(target: &Target, send: F) -> Result<FcmResponse, FcmClientError>
+where
+    F: Future<Output = Result<FcmResponse, FcmClientError>>,
+{
+    use tracing::field::Empty;
+    use tracing::Instrument;
+
+    let (target_type, target) = target_fields(target);
+    let span = tracing::info_span!(
+        "fcm.send",
+        target_type,
+        target = %target,
+        project_id = Empty,
+        message_id = Empty,
+        http_status = Empty,
+        attempts = Empty,
+        latency_ms = Empty,
+        error = Empty,
+    );
+    let started = crate::client::rt::Instant::now();
+    let result = send.instrument(span.clone()).await;
+
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+    match &result {
+        Ok(response) => {
+            span.record("http_status", response.http_status_code());
+            span.record("attempts", response.retry_history().len() + 1);
+            if let Some(name) = response.json().get("name").and_then(|name| name.as_str()) {
+                span.record("message_id", name);
+            }
+        }
+        Err(error) => {
+            if let FcmClientError::RetriesFailed { retry_history, .. } = error {
+                span.record("attempts", retry_history.len() + 1);
+            }
+            span.record("error", error_chain(error));
+        }
+    }
+    result
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) async fn send<F>(_target: &Target, send: F) -> Result<FcmResponse, FcmClientError>
+where
+    F: Future<Output = Result<FcmResponse, FcmClientError>>,
+{
+    send.await
+}
+
+/// Record the project which the message is sent to in the current
+/// `fcm.send` span and OpenTelemetry span.
+pub(crate) fn record_project_id(_project_id: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record("project_id", _project_id);
+    #[cfg(feature = "opentelemetry")]
+    {
+        use opentelemetry::trace::TraceContextExt;
+        opentelemetry::Context::current()
+            .span()
+            .set_attribute(opentelemetry::KeyValue::new("fcm.project_id", _project_id.to_string()));
+    }
+}
+
+/// Record the host and port which the attempt is sent to in the current
+/// OpenTelemetry span.
+pub(crate) fn record_server(_url: &reqwest::Url) {
+    #[cfg(feature = "opentelemetry")]
+    {
+        use opentelemetry::trace::TraceContextExt;
+        opentelemetry::Context::current()
+            .span()
+            .set_attributes(server_attributes(_url));
+    }
+}
+
+/// `server.address` and `server.port` attributes of `url`.
+#[cfg(feature = "opentelemetry")]
+fn server_attributes(url: &reqwest::Url) -> Vec<opentelemetry::KeyValue> {
+    use opentelemetry::KeyValue;
+
+    let address = url
+        .host_str()
+        .map(|host| KeyValue::new("server.address", host.to_string()));
+    let port = url
+        .port_or_known_default()
+        .map(|port| KeyValue::new("server.port", i64::from(port)));
+    address.into_iter().chain(port).collect()
+}
+
+/// Span name and destination attributes of an OpenTelemetry span for
+/// `target`. Registration tokens are not recorded.
+#[cfg(feature = "opentelemetry")]
+fn otel_destination(target: &Target) -> (String, opentelemetry::KeyValue) {
+    use opentelemetry::KeyValue;
+
+    match target {
+        Target::Token(_) => (
+            "send".to_string(),
+            KeyValue::new("messaging.destination.anonymous", true),
+        ),
+        Target::Topic(topic) => (
+            format!("send {}", topic),
+            KeyValue::new("messaging.destination.name", topic.clone()),
+        ),
+        Target::Condition(condition) => (
+            "send".to_string(),
+            KeyValue::new("messaging.destination.name", condition.clone()),
+        ),
+    }
+}
+
+/// Run one send attempt in an OpenTelemetry client span, which is a child
+/// of the current context and follows the messaging semantic conventions.
+#[cfg(feature = "opentelemetry")]
+pub(crate) async fn attempt<F>(target: &Target, attempt: F) -> Result<FcmResponse, FcmClientError>
+where
+    F: Future<Output = Result<FcmResponse, FcmClientError>>,
+{
+    use opentelemetry::trace::{FutureExt, SpanKind, Status, TraceContextExt, Tracer};
+    use opentelemetry::{global, Context, KeyValue};
+
+    let tracer = global::tracer("fcm");
+    let (name, destination) = otel_destination(target);
+    let span = tracer
+        .span_builder(name)
+        .with_kind(SpanKind::Client)
+        .with_attributes([
+            KeyValue::new("messaging.system", "fcm"),
+            KeyValue::new("messaging.operation.type", "send"),
+            KeyValue::new("messaging.operation.name", "send"),
+            destination,
+        ])
+        .start_with_context(&tracer, &Context::current());
+    let cx = Context::current_with_span(span);
+    let result = attempt.with_context(cx.clone()).await;
+
+    let span = cx.span();
+    match &result {
+        Ok(response) => {
+            span.set_attribute(KeyValue::new(
+                "http.response.status_code",
+                i64::from(response.http_status_code()),
+            ));
+            if let Some(name) = response.json().get("name").and_then(|name| name.as_str()) {
+                span.set_attribute(KeyValue::new("messaging.message.id", name.to_string()));
+            }
+            if let Some(error) = response.error() {
+                let error = format!("{:?}", error);
+                span.set_attribute(KeyValue::new("error.type", error.clone()));
+                span.set_status(Status::error(error));
+            }
+        }
+        Err(error) => {
+            span.set_attribute(KeyValue::new("error.type", "client_error"));
+            span.set_status(Status::error(crate::client::logging::error_chain(error)));
+        }
+    }
+    span.end();
+    result
+}
+
+#[cfg(not(feature = "opentelemetry"))]
+pub(crate) async fn attempt<F>(_target: &Target, attempt: F) -> Result<FcmResponse, FcmClientError>
+where
+    F: Future<Output = Result<FcmResponse, FcmClientError>>,
+{
+    attempt.await
+}
+
+/// Run `fetch` in an `fcm.oauth_token` span.
+#[cfg(feature = "tracing")]
+pub(crate) async fn access_token<F, E>(fetch: F) -> Result<String, E>
+where
+    F: Future<Output = Result<String, E>>,
+    E: std::error::Error + 'static,
+{
+    use tracing::Instrument;
+
+    let span = tracing::debug_span!("fcm.oauth_token", error = tracing::field::Empty);
+    let result = fetch.instrument(span.clone()).await;
+    if let Err(error) = &result {
+        span.record("error", error_chain(error));
+    }
+    result
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) async fn access_token<F, E>(fetch: F) -> Result<String, E>
+where
+    F: Future<Output = Result<String, E>>,
+{
+    fetch.await
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_fields_mask_token() {
+        assert_eq!(
+            target_fields(&Target::Token("fG3kL9pQzW1xY2".to_string())),
+            ("token", "fG3k…1xY2".to_string())
+        );
+        assert_eq!(
+            target_fields(&Target::Topic("news".to_string())),
+            ("topic", "news".to_string())
+        );
+    }
+}
+
+#[cfg(all(test, feature = "opentelemetry"))]
+mod otel_tests {
+    use super::*;
+
+    #[test]
+    fn test_otel_destination_does_not_record_tokens() {
+        let (name, destination) = otel_destination(&Target::Token("secret-token".to_string()));
+        assert_eq!(name, "send");
+        assert_eq!(destination.key.as_str(), "messaging.destination.anonymous");
+
+        let (name, destination) = otel_destination(&Target::Topic("news".to_string()));
+        assert_eq!(name, "send news");
+        assert_eq!(destination.value.as_str(), "news");
+    }
+
+    #[test]
+    fn test_server_attributes_come_from_url() {
+        let url = reqwest::Url::parse("http://127.0.0.1:8080/v1/projects/p/messages:send").unwrap();
+        let attributes = server_attributes(&url);
+
+        assert_eq!(attributes[0].key.as_str(), "server.address");
+        assert_eq!(attributes[0].value.as_str(), "127.0.0.1");
+        assert_eq!(attributes[1].key.as_str(), "server.port");
+        assert_eq!(attributes[1].value, opentelemetry::Value::I64(8080));
+    }
+}
@@ -0,0 +1,185 @@
+//! Recording of FCM interactions to a fixture file and replaying them
+//! without network access or credentials.
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::client::raw_response::RawResponse;
+use crate::client::vcr_error::VcrError;
+
+const REDACTED: &str = "REDACTED";
+
+/// Record or replay mode for [crate::FcmClientBuilder::vcr].
+///
+/// The fixture file contains one JSON interaction per line. Access tokens
+/// are never recorded and device tokens in requests are replaced with
+/// `"REDACTED"`.
+#[derive(Debug, Clone)]
+pub enum VcrMode {
+    /// Send messages to FCM and append the interactions to the file.
+    Record(PathBuf),
+    /// Return the recorded responses in order instead of sending messages
+    /// to FCM. No credentials are needed in this mode. Sending fails if the
+    /// request does not match the recorded request.
+    Replay(PathBuf),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Interaction {
+    request: Value,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after: Option<String>,
+    body: String,
+}
+
+#[derive(Debug)]
+pub(crate) enum Vcr {
+    Record(Mutex<File>),
+    Replay(Mutex<VecDeque<Interaction>>),
+}
+
+impl Vcr {
+    pub fn new(mode: &VcrMode) -> Result<Self, VcrError> {
+        match mode {
+            VcrMode::Record(path) => {
+                let file = OpenOptions::new().create(true).append(true).open(path)?;
+                Ok(Vcr::Record(Mutex::new(file)))
+            }
+            VcrMode::Replay(path) => {
+                let mut interactions = VecDeque::new();
+                for line in BufReader::new(File::open(path)?).lines() {
+                    let line = line?;
+                    if !line.trim().is_empty() {
+                        interactions.push_back(serde_json::from_str(&line)?);
+                    }
+                }
+                Ok(Vcr::Replay(Mutex::new(interactions)))
+            }
+        }
+    }
+
+    pub fn is_replay(&self) -> bool {
+        matches!(self, Vcr::Replay(_))
+    }
+
+    pub fn record(&self, request: &impl Serialize, response: &RawResponse) -> Result<(), VcrError> {
+        let file = match self {
+            Vcr::Record(file) => file,
+            Vcr::Replay(_) => return Ok(()),
+        };
+        let interaction = Interaction {
+            request: redact(serde_json::to_value(request)?),
+            response: RecordedResponse {
+                status: response.http_status_code,
+                retry_after: response.retry_after.clone(),
+                body: String::from_utf8_lossy(&response.body).into_owned(),
+            },
+        };
+        let mut line = serde_json::to_vec(&interaction)?;
+        line.push(b'\n');
+        file.lock().unwrap().write_all(&line)?;
+        Ok(())
+    }
+
+    pub fn replay(&self, request: &impl Serialize) -> Result<RawResponse, VcrError> {
+        let interactions = match self {
+            Vcr::Replay(interactions) => interactions,
+            Vcr::Record(_) => return Err(VcrError::Exhausted),
+        };
+        let actual = redact(serde_json::to_value(request)?);
+        let interaction = interactions.lock().unwrap().pop_front().ok_or(VcrError::Exhausted)?;
+        if interaction.request != actual {
+            return Err(VcrError::Mismatch {
+                expected: interaction.request.to_string(),
+                actual: actual.to_string(),
+            });
+        }
+        Ok(RawResponse {
+            http_status_code: interaction.response.status,
+            retry_after: interaction.response.retry_after,
+            headers: Default::default(),
+            body: interaction.response.body.into_bytes(),
+        })
+    }
+}
+
+fn redact(mut request: Value) -> Value {
+    if let Some(token) = request.pointer_mut("/message/token") {
+        *token = Value::String(REDACTED.to_string());
+    }
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fixture_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("fcm-vcr-{}-{}.jsonl", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let path = fixture_path("record-and-replay");
+        let request = json!({ "message": { "token": "secret-device-token", "data": { "a": "b" } } });
+        let response = RawResponse {
+            http_status_code: 429,
+            retry_after: Some("10".to_string()),
+            headers: Default::default(),
+            body: br#"{"error":{"status":"RESOURCE_EXHAUSTED"}}"#.to_vec(),
+        };
+
+        Vcr::new(&VcrMode::Record(path.clone()))
+            .unwrap()
+            .record(&request, &response)
+            .unwrap();
+
+        let fixture = std::fs::read_to_string(&path).unwrap();
+        assert!(!fixture.contains("secret-device-token"));
+
+        let vcr = Vcr::new(&VcrMode::Replay(path.clone())).unwrap();
+        let replayed = vcr.replay(&request).unwrap();
+        assert_eq!(response.http_status_code, replayed.http_status_code);
+        assert_eq!(response.retry_after, replayed.retry_after);
+        assert_eq!(response.body, replayed.body);
+        assert!(matches!(vcr.replay(&request), Err(VcrError::Exhausted)));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_rejects_different_request() {
+        let path = fixture_path("mismatch");
+        let response = RawResponse {
+            http_status_code: 200,
+            retry_after: None,
+            headers: Default::default(),
+            body: b"{}".to_vec(),
+        };
+        Vcr::new(&VcrMode::Record(path.clone()))
+            .unwrap()
+            .record(&json!({ "message": { "topic": "a" } }), &response)
+            .unwrap();
+
+        let vcr = Vcr::new(&VcrMode::Replay(path.clone())).unwrap();
+        let result = vcr.replay(&json!({ "message": { "topic": "b" } }));
+        assert!(matches!(result, Err(VcrError::Mismatch { .. })));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,14 @@
+/// Error of recording or replaying FCM interactions with the `vcr`
+/// feature. The type exists without the feature, so that
+/// [crate::FcmClientError::Vcr] does not depend on it.
+#[derive(thiserror::Error, Debug)]
+pub enum VcrError {
+    #[error("Fixture file I/O error")]
+    Io(#[from] std::io::Error),
+    #[error("Fixture file JSON error")]
+    Json(#[from] serde_json::Error),
+    #[error("All recorded interactions are already replayed")]
+    Exhausted,
+    #[error("Request does not match the recorded request, expected: {expected}, actual: {actual}")]
+    Mismatch { expected: String, actual: String },
+}
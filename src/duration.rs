@@ -0,0 +1,101 @@
+//! Serialization of [Duration] in the protobuf JSON format, for example
+//! `"3.5s"`.
+//!
+//! <https://developers.google.com/protocol-buffers/docs/reference/google.protobuf#google.protobuf.Duration>
+
+use std::time::Duration;
+
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+/// Format `duration` as seconds with 0, 3, 6 or 9 fractional digits.
+pub(crate) fn to_protobuf_string(duration: &Duration) -> String {
+    let seconds = duration.as_secs();
+    let nanos = duration.subsec_nanos();
+    if nanos == 0 {
+        format!("{}s", seconds)
+    } else if nanos.is_multiple_of(1_000_000) {
+        format!("{}.{:03}s", seconds, nanos / 1_000_000)
+    } else if nanos.is_multiple_of(1_000) {
+        format!("{}.{:06}s", seconds, nanos / 1_000)
+    } else {
+        format!("{}.{:09}s", seconds, nanos)
+    }
+}
+
+/// Parse a duration formatted like [to_protobuf_string]. Negative
+/// durations are not supported.
+pub(crate) fn from_protobuf_string(s: &str) -> Option<Duration> {
+    let (seconds, fraction) = match s.strip_suffix('s')?.split_once('.') {
+        Some((seconds, fraction)) => (seconds, fraction),
+        None => (s.strip_suffix('s')?, ""),
+    };
+    if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        format!("{:0<9}", fraction).parse().ok()?
+    };
+    Some(Duration::new(seconds.parse().ok()?, nanos))
+}
+
+pub(crate) fn serialize<S>(duration: &Duration, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_str(&to_protobuf_string(duration))
+}
+
+pub(crate) fn serialize_option<S>(duration: &Option<Duration>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match duration {
+        Some(duration) => s.serialize_str(&to_protobuf_string(duration)),
+        None => s.serialize_none(),
+    }
+}
+
+pub(crate) fn deserialize_option<'de, D>(d: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(d)? {
+        Some(s) => from_protobuf_string(&s)
+            .map(Some)
+            .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Str(&s), &"a duration like \"3.5s\"")),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protobuf_duration_format() {
+        assert_eq!("3600s", to_protobuf_string(&Duration::from_secs(3600)));
+        assert_eq!("0s", to_protobuf_string(&Duration::ZERO));
+        assert_eq!("1.500s", to_protobuf_string(&Duration::from_millis(1500)));
+        assert_eq!("0.000001s", to_protobuf_string(&Duration::from_micros(1)));
+        assert_eq!("3.000000001s", to_protobuf_string(&Duration::new(3, 1)));
+    }
+
+    #[test]
+    fn test_parse_protobuf_duration() {
+        for duration in [
+            Duration::from_secs(3600),
+            Duration::ZERO,
+            Duration::from_millis(1500),
+            Duration::from_micros(1),
+            Duration::new(3, 1),
+        ] {
+            assert_eq!(Some(duration), from_protobuf_string(&to_protobuf_string(&duration)));
+        }
+        assert_eq!(Some(Duration::from_millis(500)), from_protobuf_string("0.5s"));
+        assert_eq!(None, from_protobuf_string("-1s"));
+        assert_eq!(None, from_protobuf_string("1.0000000001s"));
+        assert_eq!(None, from_protobuf_string("10"));
+    }
+}
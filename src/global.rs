@@ -0,0 +1,128 @@
+//! Process wide registry of [FcmClient]s for code which can not pass the
+//! client through every layer.
+//!
+//! Every client is registered under a key type, usually one per Firebase
+//! project:
+//!
+//! ```no_run
+//! use fcm::{global, ClientKey, FcmClient};
+//!
+//! struct Marketing;
+//! impl ClientKey for Marketing {}
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = FcmClient::builder()
+//!     .service_account_key_json_path("marketing_key.json")
+//!     .build()
+//!     .await?;
+//! global().init::<Marketing>(client)?;
+//!
+//! // Anywhere else in the application.
+//! let client = global().get::<Marketing>()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::any::{type_name, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::FcmClient;
+
+/// Key type of a client in the [Registry].
+pub trait ClientKey: 'static {}
+
+/// Key for applications which use only one client.
+#[derive(Debug)]
+pub struct DefaultClient;
+
+impl ClientKey for DefaultClient {}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    #[error("Client {0} is already initialized")]
+    AlreadyInitialized(&'static str),
+    #[error("Client {0} is not initialized")]
+    NotInitialized(&'static str),
+}
+
+/// Registry of clients by [ClientKey]. Use [global] to access the process
+/// wide registry.
+#[derive(Default)]
+pub struct Registry {
+    clients: RwLock<HashMap<TypeId, Arc<FcmClient>>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `client` under `K`. A key can be initialized only once.
+    pub fn init<K: ClientKey>(&self, client: FcmClient) -> Result<Arc<FcmClient>, RegistryError> {
+        let mut clients = self.clients.write().unwrap();
+        if clients.contains_key(&TypeId::of::<K>()) {
+            return Err(RegistryError::AlreadyInitialized(type_name::<K>()));
+        }
+        let client = Arc::new(client);
+        clients.insert(TypeId::of::<K>(), client.clone());
+        Ok(client)
+    }
+
+    pub fn get<K: ClientKey>(&self) -> Result<Arc<FcmClient>, RegistryError> {
+        self.clients
+            .read()
+            .unwrap()
+            .get(&TypeId::of::<K>())
+            .cloned()
+            .ok_or(RegistryError::NotInitialized(type_name::<K>()))
+    }
+
+    /// Remove the client registered under `K`, for example to close it
+    /// during shutdown.
+    pub fn remove<K: ClientKey>(&self) -> Option<Arc<FcmClient>> {
+        self.clients.write().unwrap().remove(&TypeId::of::<K>())
+    }
+}
+
+/// Process wide client [Registry].
+pub fn global() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Registry::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ProjectA;
+    impl ClientKey for ProjectA {}
+
+    async fn new_client() -> FcmClient {
+        FcmClient::builder()
+            .access_token("project-a", "token")
+            .build()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_registry_keys_clients_by_type() {
+        let registry = Registry::new();
+        assert!(matches!(
+            registry.get::<ProjectA>(),
+            Err(RegistryError::NotInitialized(_))
+        ));
+
+        let client = registry.init::<ProjectA>(new_client().await).unwrap();
+
+        assert!(Arc::ptr_eq(&client, &registry.get::<ProjectA>().unwrap()));
+        assert!(registry.get::<DefaultClient>().is_err());
+        assert!(matches!(
+            registry.init::<ProjectA>(new_client().await),
+            Err(RegistryError::AlreadyInitialized(_))
+        ));
+        assert!(registry.remove::<ProjectA>().is_some());
+        assert!(registry.get::<ProjectA>().is_err());
+    }
+}
@@ -36,6 +36,7 @@
 //!         webpush: None,
 //!         apns: None,
 //!         fcm_options: None,
+//!         excluded_platforms: Vec::new(),
 //!     };
 //!
 //!     let response = client.send(message).await?;
@@ -45,13 +46,29 @@
 //! }
 //! ```
 
+#[cfg(not(target_arch = "wasm32"))]
 pub use yup_oauth2;
 
+mod macros;
+
 pub(crate) mod android;
 pub(crate) mod apns;
+pub(crate) mod duration;
 pub mod message;
 pub(crate) mod notification;
 pub(crate) mod web;
 
 mod client;
 pub use crate::client::*;
+
+mod global;
+pub use crate::global::*;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+mod router;
+pub use crate::router::*;
+
+mod token_store;
+pub use crate::token_store::*;
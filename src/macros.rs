@@ -0,0 +1,97 @@
+/// Build a [Message](crate::message::Message) from a template.
+///
+/// The macro expands to [MessageBuilder](crate::message::MessageBuilder)
+/// calls, so mistakes are reported by the compiler:
+///
+/// - The template must start with exactly one target: `token`, `topic` or `condition`.
+/// - Unknown field names (for example `titel` instead of `title`) do not compile.
+/// - Data values must be strings, as required by FCM.
+///
+/// ```rust
+/// let message = fcm::message! {
+///     token: "device_token",
+///     notification: {
+///         title: "Hello",
+///         body: "World",
+///     },
+///     data: {
+///         "order_id": "1234",
+///     },
+/// };
+///
+/// assert!(message.notification.is_some());
+/// ```
+///
+/// Platform specific options can be given as expressions:
+///
+/// ```rust
+/// use fcm::message::{AndroidConfig, AndroidMessagePriority};
+///
+/// let message = fcm::message! {
+///     topic: "news",
+///     android: AndroidConfig {
+///         priority: Some(AndroidMessagePriority::High),
+///         ..Default::default()
+///     },
+/// };
+/// ```
+///
+/// Non-string data values are rejected:
+///
+/// ```compile_fail
+/// let message = fcm::message! {
+///     token: "device_token",
+///     data: { "count": 1 },
+/// };
+/// ```
+///
+/// And so are misspelled fields:
+///
+/// ```compile_fail
+/// let message = fcm::message! {
+///     token: "device_token",
+///     notification: { titel: "Hello" },
+/// };
+/// ```
+#[macro_export]
+macro_rules! message {
+    (@fields $builder:expr;) => {
+        $builder.build()
+    };
+    (@fields $builder:expr; notification: { $($field:ident : $value:expr),* $(,)? } $(, $($rest:tt)*)?) => {
+        $crate::message!(@fields $builder.notification($crate::message::Notification {
+            $($field: ::std::option::Option::Some(::std::convert::Into::into($value)),)*
+            ..::std::default::Default::default()
+        }); $($($rest)*)?)
+    };
+    (@fields $builder:expr; data: { $($key:literal : $value:expr),* $(,)? } $(, $($rest:tt)*)?) => {
+        $crate::message!(@fields $builder$(.data_entry($key, $value))*; $($($rest)*)?)
+    };
+    (@fields $builder:expr; android: $value:expr $(, $($rest:tt)*)?) => {
+        $crate::message!(@fields $builder.android($value); $($($rest)*)?)
+    };
+    (@fields $builder:expr; webpush: $value:expr $(, $($rest:tt)*)?) => {
+        $crate::message!(@fields $builder.webpush($value); $($($rest)*)?)
+    };
+    (@fields $builder:expr; apns: $value:expr $(, $($rest:tt)*)?) => {
+        $crate::message!(@fields $builder.apns($value); $($($rest)*)?)
+    };
+    (@fields $builder:expr; fcm_options: $value:expr $(, $($rest:tt)*)?) => {
+        $crate::message!(@fields $builder.fcm_options($value); $($($rest)*)?)
+    };
+    (token: $target:expr $(, $($rest:tt)*)?) => {
+        $crate::message!(@fields $crate::message::Message::builder(
+            $crate::message::Target::Token(::std::string::ToString::to_string(&$target))
+        ); $($($rest)*)?)
+    };
+    (topic: $target:expr $(, $($rest:tt)*)?) => {
+        $crate::message!(@fields $crate::message::Message::builder(
+            $crate::message::Target::Topic(::std::string::ToString::to_string(&$target))
+        ); $($($rest)*)?)
+    };
+    (condition: $target:expr $(, $($rest:tt)*)?) => {
+        $crate::message!(@fields $crate::message::Message::builder(
+            $crate::message::Target::Condition(::std::string::ToString::to_string(&$target))
+        ); $($($rest)*)?)
+    };
+}
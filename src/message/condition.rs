@@ -0,0 +1,182 @@
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Not};
+
+use crate::message::Target;
+
+/// FCM accepts at most this many topics in one condition.
+/// <https://firebase.google.com/docs/cloud-messaging/send-message#send-messages-to-topics>
+pub const MAX_CONDITION_TOPICS: usize = 5;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ConditionError {
+    #[error("Condition contains {count} topics, maximum is {MAX_CONDITION_TOPICS}")]
+    TooManyTopics { count: usize },
+    #[error("Topic name is not valid: {0:?}")]
+    InvalidTopicName(String),
+}
+
+/// Topic used as an operand of a [Condition].
+///
+/// ```rust
+/// use fcm::message::{Target, Topic};
+///
+/// let condition = Topic::new("dogs") & (Topic::new("food") | Topic::new("toys"));
+/// assert_eq!(
+///     condition.build().unwrap(),
+///     Target::Condition("'dogs' in topics && ('food' in topics || 'toys' in topics)".to_string()),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Topic(String);
+
+impl Topic {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    pub fn and(self, other: impl Into<Condition>) -> Condition {
+        Condition::from(self).and(other)
+    }
+
+    pub fn or(self, other: impl Into<Condition>) -> Condition {
+        Condition::from(self).or(other)
+    }
+
+    /// Topic names may only contain characters `[a-zA-Z0-9-_.~%]`.
+    fn is_valid(&self) -> bool {
+        !self.0.is_empty()
+            && self
+                .0
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '~' | '%'))
+    }
+}
+
+/// Boolean expression of topics which renders to the FCM condition syntax.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Topic(Topic),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    pub fn and(self, other: impl Into<Condition>) -> Condition {
+        Condition::And(Box::new(self), Box::new(other.into()))
+    }
+
+    pub fn or(self, other: impl Into<Condition>) -> Condition {
+        Condition::Or(Box::new(self), Box::new(other.into()))
+    }
+
+    /// Topics in the order they appear in the expression.
+    pub fn topics(&self) -> Vec<&Topic> {
+        match self {
+            Condition::Topic(topic) => vec![topic],
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                let mut topics = left.topics();
+                topics.extend(right.topics());
+                topics
+            }
+            Condition::Not(inner) => inner.topics(),
+        }
+    }
+
+    /// Check the topic count and topic names and create [Target::Condition].
+    pub fn build(&self) -> Result<Target, ConditionError> {
+        let topics = self.topics();
+        if topics.len() > MAX_CONDITION_TOPICS {
+            return Err(ConditionError::TooManyTopics { count: topics.len() });
+        }
+        if let Some(topic) = topics.into_iter().find(|topic| !topic.is_valid()) {
+            return Err(ConditionError::InvalidTopicName(topic.name().to_string()));
+        }
+
+        Ok(Target::Condition(self.to_string()))
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>, parent_is_and: bool) -> fmt::Result {
+        match self {
+            Condition::Or(..) if parent_is_and => write!(f, "({})", self),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Topic(topic) => write!(f, "'{}' in topics", topic.name()),
+            Condition::And(left, right) => {
+                left.fmt_operand(f, true)?;
+                write!(f, " && ")?;
+                right.fmt_operand(f, true)
+            }
+            Condition::Or(left, right) => {
+                left.fmt_operand(f, false)?;
+                write!(f, " || ")?;
+                right.fmt_operand(f, false)
+            }
+            Condition::Not(inner) => write!(f, "!({})", inner),
+        }
+    }
+}
+
+impl From<Topic> for Condition {
+    fn from(topic: Topic) -> Self {
+        Condition::Topic(topic)
+    }
+}
+
+impl<T: Into<Condition>> BitAnd<T> for Condition {
+    type Output = Condition;
+
+    fn bitand(self, rhs: T) -> Condition {
+        self.and(rhs)
+    }
+}
+
+impl<T: Into<Condition>> BitOr<T> for Condition {
+    type Output = Condition;
+
+    fn bitor(self, rhs: T) -> Condition {
+        self.or(rhs)
+    }
+}
+
+impl Not for Condition {
+    type Output = Condition;
+
+    fn not(self) -> Condition {
+        Condition::Not(Box::new(self))
+    }
+}
+
+impl<T: Into<Condition>> BitAnd<T> for Topic {
+    type Output = Condition;
+
+    fn bitand(self, rhs: T) -> Condition {
+        self.and(rhs)
+    }
+}
+
+impl<T: Into<Condition>> BitOr<T> for Topic {
+    type Output = Condition;
+
+    fn bitor(self, rhs: T) -> Condition {
+        self.or(rhs)
+    }
+}
+
+impl Not for Topic {
+    type Output = Condition;
+
+    fn not(self) -> Condition {
+        !Condition::from(self)
+    }
+}
@@ -0,0 +1,142 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Keys which FCM does not allow in the data payload.
+/// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#resource:-message>
+const RESERVED_KEYS: &[&str] = &["from", "notification", "message_type"];
+const RESERVED_KEY_PREFIXES: &[&str] = &["google", "gcm"];
+
+#[derive(thiserror::Error, Debug)]
+pub enum DataError {
+    #[error("Data serialization failed")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Data must serialize to a JSON object")]
+    NotAnObject,
+    #[error("Data key {0:?} is reserved by FCM")]
+    ReservedKey(String),
+    #[error("Data value of key {0:?} is not a string")]
+    NonStringValue(String),
+    #[error("Data key {key:?} contains a NUL character at byte {position}")]
+    NulInKey { key: String, position: usize },
+    #[error("Data value of key {key:?} contains a NUL character at byte {position}")]
+    NulInValue { key: String, position: usize },
+    #[error("Data key {key_lossy:?} is not valid UTF-8 after byte {valid_up_to}")]
+    InvalidUtf8Key { key_lossy: String, valid_up_to: usize },
+    #[error("Data value of key {key:?} is not valid UTF-8 after byte {valid_up_to}")]
+    InvalidUtf8Value { key: String, valid_up_to: usize },
+}
+
+/// Serialize `value` into a data payload where every value is a string.
+///
+/// Strings are kept as is, numbers and booleans are converted to strings,
+/// arrays and objects are converted to JSON strings and `null` values
+/// are left out.
+pub fn to_data_payload<T: Serialize + ?Sized>(value: &T) -> Result<Value, DataError> {
+    let object = match serde_json::to_value(value)? {
+        Value::Object(object) => object,
+        _ => return Err(DataError::NotAnObject),
+    };
+
+    let mut data = Map::with_capacity(object.len());
+    for (key, value) in object {
+        let value = match value {
+            Value::Null => continue,
+            Value::String(value) => value,
+            Value::Bool(value) => value.to_string(),
+            Value::Number(value) => value.to_string(),
+            value @ (Value::Array(_) | Value::Object(_)) => serde_json::to_string(&value)?,
+        };
+        check_entry(&key, &value)?;
+        data.insert(key, Value::String(value));
+    }
+
+    Ok(Value::Object(data))
+}
+
+/// Create a data payload from raw key/value bytes, for example from an
+/// upstream system which does not guarantee UTF-8. Errors tell the key and
+/// the byte offset of the first invalid byte.
+pub fn data_from_bytes<I, K, V>(entries: I) -> Result<Value, DataError>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+{
+    let mut data = Map::new();
+    for (key, value) in entries {
+        let key = std::str::from_utf8(key.as_ref()).map_err(|error| DataError::InvalidUtf8Key {
+            key_lossy: String::from_utf8_lossy(key.as_ref()).into_owned(),
+            valid_up_to: error.valid_up_to(),
+        })?;
+        let value = std::str::from_utf8(value.as_ref()).map_err(|error| DataError::InvalidUtf8Value {
+            key: key.to_string(),
+            valid_up_to: error.valid_up_to(),
+        })?;
+        check_entry(key, value)?;
+        data.insert(key.to_string(), Value::String(value.to_string()));
+    }
+    Ok(Value::Object(data))
+}
+
+/// Check that `data` is a valid data payload: a JSON object of string
+/// values without reserved keys or NUL characters.
+pub fn validate_data(data: &Value) -> Result<(), DataError> {
+    let object = data.as_object().ok_or(DataError::NotAnObject)?;
+    for (key, value) in object {
+        let value = value.as_str().ok_or_else(|| DataError::NonStringValue(key.clone()))?;
+        check_entry(key, value)?;
+    }
+    Ok(())
+}
+
+/// Normalize the keys and string values of `data` to Unicode
+/// Normalization Form C, so that equal text is sent as equal bytes.
+#[cfg(feature = "unicode-normalization")]
+pub fn normalize_data_nfc(data: &mut Value) {
+    use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+    let object = match data {
+        Value::Object(object) => object,
+        _ => return,
+    };
+    if object
+        .iter()
+        .all(|(key, value)| is_nfc(key) && value.as_str().is_none_or(is_nfc))
+    {
+        return;
+    }
+    *object = std::mem::take(object)
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(value) => Value::String(value.nfc().collect()),
+                value => value,
+            };
+            (key.nfc().collect(), value)
+        })
+        .collect();
+}
+
+fn check_entry(key: &str, value: &str) -> Result<(), DataError> {
+    if is_reserved_key(key) {
+        return Err(DataError::ReservedKey(key.to_string()));
+    }
+    if let Some(position) = key.find('\0') {
+        return Err(DataError::NulInKey {
+            key: key.to_string(),
+            position,
+        });
+    }
+    if let Some(position) = value.find('\0') {
+        return Err(DataError::NulInValue {
+            key: key.to_string(),
+            position,
+        });
+    }
+    Ok(())
+}
+
+fn is_reserved_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    RESERVED_KEYS.contains(&key.as_str()) || RESERVED_KEY_PREFIXES.iter().any(|prefix| key.starts_with(prefix))
+}
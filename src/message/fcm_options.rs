@@ -1,8 +1,91 @@
-use serde::Serialize;
+use std::convert::TryFrom;
+use std::fmt;
 
-#[derive(Debug, Default, Serialize)]
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Maximum length of an [AnalyticsLabel].
+pub const MAX_ANALYTICS_LABEL_LENGTH: usize = 50;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AnalyticsLabelError {
+    #[error("Analytics label must not be empty")]
+    Empty,
+    #[error("Analytics label is {length} characters long, maximum is {MAX_ANALYTICS_LABEL_LENGTH}")]
+    TooLong { length: usize },
+    #[error("Analytics label contains invalid character {0:?}")]
+    InvalidCharacter(char),
+}
+
+/// Label associated with the message's analytics data. FCM ignores labels
+/// which do not match `^[a-zA-Z0-9-_.~%]{1,50}$`, so the label is validated
+/// when it is created.
+///
+/// ```rust
+/// use fcm::message::AnalyticsLabel;
+///
+/// assert!(AnalyticsLabel::new("spring_sale-2024").is_ok());
+/// assert!(AnalyticsLabel::new("spring sale").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub struct AnalyticsLabel(String);
+
+impl AnalyticsLabel {
+    pub fn new(label: impl Into<String>) -> Result<Self, AnalyticsLabelError> {
+        let label = label.into();
+        if label.is_empty() {
+            return Err(AnalyticsLabelError::Empty);
+        }
+        if let Some(c) = label
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || "-_.~%".contains(*c)))
+        {
+            return Err(AnalyticsLabelError::InvalidCharacter(c));
+        }
+        // Only ASCII characters are left, so the byte length is the character count.
+        if label.len() > MAX_ANALYTICS_LABEL_LENGTH {
+            return Err(AnalyticsLabelError::TooLong { length: label.len() });
+        }
+        Ok(Self(label))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for AnalyticsLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for AnalyticsLabel {
+    type Error = AnalyticsLabelError;
+
+    fn try_from(label: String) -> Result<Self, Self::Error> {
+        Self::new(label)
+    }
+}
+
+impl Serialize for AnalyticsLabel {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages?authuser=0#fcmoptions>
 pub struct FcmOptions {
     /// Label associated with the message's analytics data.
-    pub analytics_label: String,
+    pub analytics_label: AnalyticsLabel,
+}
+
+impl FcmOptions {
+    pub fn new(analytics_label: AnalyticsLabel) -> Self {
+        Self { analytics_label }
+    }
 }
@@ -0,0 +1,84 @@
+use serde_json::{Map, Value};
+
+use crate::message::{object_entry, AndroidConfig, AndroidNotification, ApnsConfig};
+
+/// Localized title and body which are set for both Android and APNs with
+/// [crate::message::MessageBuilder::localized].
+///
+/// The keys refer to string resources on Android and to
+/// `Localizable.strings` entries on iOS, so the apps must use the same
+/// keys on both platforms.
+///
+/// ```rust
+/// use fcm::message::{LocalizedNotification, Message, Target};
+///
+/// let message = Message::builder(Target::Topic("orders".to_string()))
+///     .localized(
+///         LocalizedNotification::new()
+///             .title("order_shipped_title", vec![])
+///             .body("order_shipped_body", vec!["1234".to_string()]),
+///     )
+///     .build();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LocalizedNotification {
+    title: Option<(String, Vec<String>)>,
+    body: Option<(String, Vec<String>)>,
+}
+
+impl LocalizedNotification {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Localize the title with `key` formatted with `args`.
+    pub fn title(mut self, key: impl Into<String>, args: Vec<String>) -> Self {
+        self.title = Some((key.into(), args));
+        self
+    }
+
+    /// Localize the body with `key` formatted with `args`.
+    pub fn body(mut self, key: impl Into<String>, args: Vec<String>) -> Self {
+        self.body = Some((key.into(), args));
+        self
+    }
+
+    /// Set the localization fields of the Android notification, keeping
+    /// its other fields.
+    pub fn apply_android(&self, config: &mut AndroidConfig) {
+        let notification = config.notification.get_or_insert_with(AndroidNotification::default);
+        if let Some((key, args)) = self.title.clone() {
+            notification.title_loc_key = Some(key);
+            notification.title_loc_args = Some(args);
+        }
+        if let Some((key, args)) = self.body.clone() {
+            notification.body_loc_key = Some(key);
+            notification.body_loc_args = Some(args);
+        }
+    }
+
+    /// Set the localization keys of the `aps.alert` dictionary in the APNs
+    /// payload, keeping the other payload keys.
+    pub fn apply_apns(&self, config: &mut ApnsConfig) {
+        let payload = config.payload.get_or_insert_with(|| Value::Object(Map::new()));
+        if !payload.is_object() {
+            *payload = Value::Object(Map::new());
+        }
+        let aps = object_entry(payload.as_object_mut().unwrap(), "aps");
+        if let Some(alert) = aps.get_mut("alert").filter(|alert| alert.is_string()) {
+            // A string alert is the alert body.
+            let mut object = Map::new();
+            object.insert("body".to_string(), alert.take());
+            *alert = Value::Object(object);
+        }
+        let alert = object_entry(aps, "alert");
+        if let Some((key, args)) = &self.title {
+            alert.insert("title-loc-key".to_string(), key.clone().into());
+            alert.insert("title-loc-args".to_string(), args.clone().into());
+        }
+        if let Some((key, args)) = &self.body {
+            alert.insert("loc-key".to_string(), key.clone().into());
+            alert.insert("loc-args".to_string(), args.clone().into());
+        }
+    }
+}
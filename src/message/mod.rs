@@ -1,16 +1,32 @@
+pub(crate) mod condition;
+pub(crate) mod data;
 pub(crate) mod fcm_options;
+pub(crate) mod localization;
+pub(crate) mod platform;
+pub(crate) mod priority;
+pub(crate) mod schema;
+pub(crate) mod size;
 pub(crate) mod target;
+pub(crate) mod template;
 
 #[cfg(test)]
 mod tests;
 
 use serde::ser::SerializeMap;
-use serde::Serialize;
 use serde::Serializer;
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
+pub use crate::message::condition::*;
+pub use crate::message::data::*;
 pub use crate::message::fcm_options::*;
+pub use crate::message::localization::*;
+pub use crate::message::platform::*;
+pub use crate::message::priority::*;
+pub use crate::message::schema::*;
+pub use crate::message::size::*;
 pub use crate::message::target::*;
+pub use crate::message::template::*;
 
 pub use crate::notification::*;
 
@@ -25,9 +41,15 @@ pub use crate::android::visibility::*;
 
 pub use crate::apns::apns_config::*;
 pub use crate::apns::apns_fcm_options::*;
+pub use crate::apns::apns_headers::*;
+pub use crate::apns::aps::*;
+pub use crate::apns::live_activity::*;
+pub use crate::apns::sound::*;
 
+pub use crate::web::web_notification::*;
 pub use crate::web::webpush_config::*;
 pub use crate::web::webpush_fcm_options::*;
+pub use crate::web::webpush_headers::*;
 
 fn output_target<S>(target: &Target, s: S) -> Result<S::Ok, S::Error>
 where
@@ -42,7 +64,7 @@ where
     map.end()
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// A `Message` instance is the main object to send to the FCM API.
 /// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages?authuser=0#resource:-message>
 pub struct Message {
@@ -73,6 +95,52 @@ pub struct Message {
     /// Target to send a message to.
     #[serde(flatten, serialize_with = "output_target")]
     pub target: Target,
+
+    /// Platforms removed with [Message::platforms]. Delivery profiles and
+    /// [Message::set_priority] add no configuration for them. It is not
+    /// sent to FCM.
+    #[serde(skip)]
+    pub excluded_platforms: Vec<Platform>,
+}
+
+impl Message {
+    /// Start building a message for the given target.
+    pub fn builder(target: Target) -> MessageBuilder {
+        MessageBuilder::new(target)
+    }
+
+    /// Set the data payload from any serializable value. See [to_data_payload]
+    /// for how the values are converted to strings.
+    pub fn data_from<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), DataError> {
+        self.data = Some(to_data_payload(value)?);
+        Ok(())
+    }
+
+    /// Send this message with the named client delivery profile, see
+    /// [crate::FcmClient::send_profiled].
+    pub fn profile(self, profile: impl Into<String>) -> ProfiledMessage {
+        ProfiledMessage {
+            message: self,
+            profile: Some(profile.into()),
+        }
+    }
+}
+
+/// A [Message] with the name of the client delivery profile to send it
+/// with. Unlike the profile passed to [crate::FcmClient::send_with_profile],
+/// it is kept when the message is stored as JSON, for example in a job
+/// queue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfiledMessage {
+    pub message: Message,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+impl From<Message> for ProfiledMessage {
+    fn from(message: Message) -> Self {
+        Self { message, profile: None }
+    }
 }
 
 impl AsRef<Message> for Message {
@@ -81,23 +149,152 @@ impl AsRef<Message> for Message {
     }
 }
 
+/// Builder for [Message].
+///
+/// ```rust
+/// use fcm::message::{Message, Notification, Target};
+///
+/// let message = Message::builder(Target::Topic("news".to_string()))
+///     .notification(Notification {
+///         title: Some("Hello".to_string()),
+///         ..Default::default()
+///     })
+///     .data_entry("article_id", "42")
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct MessageBuilder {
+    target: Target,
+    data: Option<Value>,
+    notification: Option<Notification>,
+    android: Option<AndroidConfig>,
+    webpush: Option<WebpushConfig>,
+    apns: Option<ApnsConfig>,
+    fcm_options: Option<FcmOptions>,
+    localized: Option<LocalizedNotification>,
+}
+
+impl MessageBuilder {
+    pub fn new(target: Target) -> Self {
+        Self {
+            target,
+            data: None,
+            notification: None,
+            android: None,
+            webpush: None,
+            apns: None,
+            fcm_options: None,
+            localized: None,
+        }
+    }
+
+    /// Set the whole data payload. This replaces entries added
+    /// with `data_entry`.
+    pub fn data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Set the whole data payload from any serializable value. See
+    /// [to_data_payload] for how the values are converted to strings.
+    pub fn data_from<T: Serialize + ?Sized>(mut self, value: &T) -> Result<Self, DataError> {
+        self.data = Some(to_data_payload(value)?);
+        Ok(self)
+    }
+
+    /// Add a single key/value pair to the data payload. FCM requires
+    /// data values to be strings.
+    pub fn data_entry(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let data = self.data.get_or_insert_with(|| Value::Object(Default::default()));
+        if let Value::Object(map) = data {
+            map.insert(key.into(), Value::String(value.into()));
+        }
+        self
+    }
+
+    pub fn notification(mut self, notification: Notification) -> Self {
+        self.notification = Some(notification);
+        self
+    }
+
+    pub fn android(mut self, android: AndroidConfig) -> Self {
+        self.android = Some(android);
+        self
+    }
+
+    pub fn webpush(mut self, webpush: WebpushConfig) -> Self {
+        self.webpush = Some(webpush);
+        self
+    }
+
+    pub fn apns(mut self, apns: ApnsConfig) -> Self {
+        self.apns = Some(apns);
+        self
+    }
+
+    pub fn fcm_options(mut self, fcm_options: FcmOptions) -> Self {
+        self.fcm_options = Some(fcm_options);
+        self
+    }
+
+    /// Localize the notification on both Android and APNs. The
+    /// localization fields are merged into the Android and APNs
+    /// configuration when the message is built.
+    pub fn localized(mut self, localized: LocalizedNotification) -> Self {
+        self.localized = Some(localized);
+        self
+    }
+
+    pub fn build(mut self) -> Message {
+        if let Some(localized) = &self.localized {
+            localized.apply_android(self.android.get_or_insert_with(Default::default));
+            localized.apply_apns(self.apns.get_or_insert_with(Default::default));
+        }
+        Message {
+            data: self.data,
+            notification: self.notification,
+            android: self.android,
+            webpush: self.webpush,
+            apns: self.apns,
+            fcm_options: self.fcm_options,
+            target: self.target,
+            excluded_platforms: Vec::new(),
+        }
+    }
+}
+
 /// Wrap the message in a "message" field
 fn is_validate_only_default(b: &bool) -> bool {
-    *b == false
+    !*b
 }
 
 #[derive(Serialize)]
-pub(crate) struct MessageWrapper<'a> {
+pub(crate) struct MessageWrapper<'a, M: ?Sized = Message> {
     #[serde(skip_serializing_if = "is_validate_only_default")]
     validate_only: bool,
-    message: &'a Message,
+    message: &'a M,
 }
 
-impl MessageWrapper<'_> {
-    pub fn new(message: &Message, dry_run: bool) -> MessageWrapper {
+impl<M: Serialize + ?Sized> MessageWrapper<'_, M> {
+    pub fn new(message: &M, dry_run: bool) -> MessageWrapper<'_, M> {
         MessageWrapper {
             validate_only: dry_run,
             message,
         }
     }
 }
+
+/// Object under `key` of a serialized message. A missing key or a value
+/// which is not an object is replaced with an empty object.
+pub(crate) fn object_entry<'a>(object: &'a mut Map<String, Value>, key: &str) -> &'a mut Map<String, Value> {
+    let entry = object.entry(key).or_insert_with(|| Value::Object(Map::new()));
+    if !entry.is_object() {
+        *entry = Value::Object(Map::new());
+    }
+    entry.as_object_mut().unwrap()
+}
+
+/// Insert `value` under `key` unless the key is already set.
+pub(crate) fn insert_missing(object: &mut Map<String, Value>, key: &str, value: impl Into<Value>) {
+    object.entry(key).or_insert_with(|| value.into());
+}
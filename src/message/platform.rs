@@ -0,0 +1,64 @@
+use crate::message::{Message, Target};
+
+/// Platform which a message can be delivered to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    Android,
+    Ios,
+    Web,
+}
+
+impl Platform {
+    pub const ALL: [Platform; 3] = [Platform::Android, Platform::Ios, Platform::Web];
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PlatformError {
+    #[error("At least one platform must be enabled")]
+    NoPlatforms,
+    #[error(
+        "Topic and condition messages reach every platform, {0:?} can be excluded only when the message has no \
+         common notification or data"
+    )]
+    SharedPayload(Platform),
+}
+
+impl Message {
+    /// Keep delivery configuration only for `platforms` and remove the
+    /// `android`, `apns` or `webpush` configuration of the other platforms.
+    ///
+    /// A token target belongs to a single device, so removing the
+    /// configuration is enough. Topic and condition targets reach devices
+    /// of every platform, so the common `notification` and `data` would
+    /// still be delivered to the excluded platforms. Such messages are
+    /// rejected and the message is left unchanged.
+    ///
+    /// The excluded platforms are kept in [Message::excluded_platforms], so
+    /// delivery profiles and [Message::set_priority] don't add their
+    /// configuration back.
+    pub fn platforms(&mut self, platforms: &[Platform]) -> Result<(), PlatformError> {
+        if platforms.is_empty() {
+            return Err(PlatformError::NoPlatforms);
+        }
+        let excluded: Vec<Platform> = Platform::ALL
+            .iter()
+            .copied()
+            .filter(|platform| !platforms.contains(platform))
+            .collect();
+        let shared_payload = self.notification.is_some() || self.data.is_some();
+        if let Some(&platform) = excluded.first() {
+            if shared_payload && !matches!(self.target, Target::Token(_)) {
+                return Err(PlatformError::SharedPayload(platform));
+            }
+        }
+        for platform in &excluded {
+            match platform {
+                Platform::Android => self.android = None,
+                Platform::Ios => self.apns = None,
+                Platform::Web => self.webpush = None,
+            }
+        }
+        self.excluded_platforms = excluded;
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::message::{
+    insert_missing, object_entry, AndroidConfig, AndroidMessagePriority, ApnsConfig, ApnsPriority, InterruptionLevel,
+    Message, Platform, Urgency, WebpushConfig,
+};
+
+/// Delivery priority of a message independent of the platform. It maps to
+/// the Android priority, the `apns-priority` header and the webpush
+/// `Urgency` header in one place:
+///
+/// | Priority      | Android  | APNs                        | Webpush    |
+/// |---------------|----------|-----------------------------|------------|
+/// | Transactional | `HIGH`   | 10, `time-sensitive` alerts | `high`     |
+/// | High          | `HIGH`   | 10                          | `high`     |
+/// | Normal        | `NORMAL` | 5                           | `normal`   |
+/// | Background    | `NORMAL` | 5                           | `very-low` |
+///
+/// APNs requires priority 5 for background notifications, so messages with
+/// the `apns-push-type: background` header or `content-available` always
+/// get APNs priority 5. Transactional messages with a visible notification
+/// also get the `time-sensitive` interruption level unless one is set, so
+/// they are shown during Focus.
+///
+/// Set it on a message with [Message::set_priority] or as a default with
+/// [crate::DeliveryProfile::priority].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeliveryPriority {
+    /// Messages the user is waiting for, such as one-time codes and
+    /// payment confirmations.
+    Transactional,
+    /// Time sensitive messages which should wake the device.
+    High,
+    /// Messages which can wait for the device to wake up.
+    Normal,
+    /// Data messages without user visible content, such as sync triggers.
+    /// APNs requires priority 5 for background notifications.
+    Background,
+}
+
+impl DeliveryPriority {
+    pub fn android(self) -> AndroidMessagePriority {
+        match self {
+            DeliveryPriority::Transactional | DeliveryPriority::High => AndroidMessagePriority::High,
+            DeliveryPriority::Normal | DeliveryPriority::Background => AndroidMessagePriority::Normal,
+        }
+    }
+
+    pub fn apns(self) -> ApnsPriority {
+        match self {
+            DeliveryPriority::Transactional | DeliveryPriority::High => ApnsPriority::Immediate,
+            DeliveryPriority::Normal | DeliveryPriority::Background => ApnsPriority::PowerConsiderate,
+        }
+    }
+
+    pub fn webpush(self) -> Urgency {
+        match self {
+            DeliveryPriority::Transactional | DeliveryPriority::High => Urgency::High,
+            DeliveryPriority::Normal => Urgency::Normal,
+            DeliveryPriority::Background => Urgency::VeryLow,
+        }
+    }
+
+    /// Set the platform priorities which are missing from the serialized
+    /// `message`, except for the `excluded` platforms.
+    pub(crate) fn fill_missing(self, message: &mut Map<String, Value>, excluded: &[Platform]) {
+        if !excluded.contains(&Platform::Android) {
+            let android = object_entry(message, "android");
+            insert_missing(
+                android,
+                "priority",
+                serde_json::to_value(self.android()).unwrap_or_default(),
+            );
+        }
+        if !excluded.contains(&Platform::Ios) {
+            let visible = message.contains_key("notification");
+            let apns = object_entry(message, "apns");
+            let background = is_apns_background(apns.get("headers"), apns.get("payload"));
+            let visible = visible || has_apns_alert(apns.get("payload"));
+            insert_missing(
+                object_entry(apns, "headers"),
+                "apns-priority",
+                self.apns_for(background).as_str(),
+            );
+            if let Some(level) = self.interruption_level(visible, background) {
+                insert_missing(
+                    object_entry(object_entry(apns, "payload"), "aps"),
+                    "interruption-level",
+                    level,
+                );
+            }
+        }
+        if !excluded.contains(&Platform::Web) {
+            let webpush_headers = object_entry(object_entry(message, "webpush"), "headers");
+            insert_missing(
+                webpush_headers,
+                "Urgency",
+                serde_json::to_value(self.webpush()).unwrap_or_default(),
+            );
+        }
+    }
+
+    fn apns_for(self, background: bool) -> ApnsPriority {
+        match background {
+            true => ApnsPriority::PowerConsiderate,
+            false => self.apns(),
+        }
+    }
+
+    fn interruption_level(self, visible: bool, background: bool) -> Option<Value> {
+        match self {
+            DeliveryPriority::Transactional if visible && !background => {
+                serde_json::to_value(InterruptionLevel::TimeSensitive).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+fn is_apns_background(headers: Option<&Value>, payload: Option<&Value>) -> bool {
+    let push_type = headers.and_then(|headers| headers.get("apns-push-type"));
+    let content_available = payload.and_then(|payload| payload.pointer("/aps/content-available"));
+    push_type.and_then(Value::as_str) == Some("background") || content_available.and_then(Value::as_u64) == Some(1)
+}
+
+fn has_apns_alert(payload: Option<&Value>) -> bool {
+    payload.and_then(|payload| payload.pointer("/aps/alert")).is_some()
+}
+
+/// Existing Android priorities map to [DeliveryPriority::High] and
+/// [DeliveryPriority::Normal].
+impl From<AndroidMessagePriority> for DeliveryPriority {
+    fn from(priority: AndroidMessagePriority) -> Self {
+        match priority {
+            AndroidMessagePriority::High => DeliveryPriority::High,
+            AndroidMessagePriority::Normal => DeliveryPriority::Normal,
+        }
+    }
+}
+
+impl Message {
+    /// Set the priority of every platform, replacing priorities which are
+    /// already set. Platforms removed with [Message::platforms] are
+    /// skipped. See [DeliveryPriority] for the rules of APNs.
+    pub fn set_priority(&mut self, priority: DeliveryPriority) {
+        if !self.excluded_platforms.contains(&Platform::Android) {
+            self.android.get_or_insert_with(AndroidConfig::default).priority = Some(priority.android());
+        }
+
+        if !self.excluded_platforms.contains(&Platform::Ios) {
+            let visible = self.notification.is_some();
+            let apns = self.apns.get_or_insert_with(ApnsConfig::default);
+            let background = is_apns_background(apns.headers.as_ref(), apns.payload.as_ref());
+            let visible = visible || has_apns_alert(apns.payload.as_ref());
+            let mut headers = apns.headers.take().unwrap_or_else(|| Value::Object(Map::new()));
+            if let Value::Object(headers) = &mut headers {
+                let apns_priority = priority.apns_for(background);
+                headers.insert("apns-priority".to_string(), apns_priority.as_str().into());
+            }
+            apns.headers = Some(headers);
+            if let Some(level) = priority.interruption_level(visible, background) {
+                let mut payload = apns.payload.take().unwrap_or_else(|| Value::Object(Map::new()));
+                if let Value::Object(payload) = &mut payload {
+                    insert_missing(object_entry(payload, "aps"), "interruption-level", level);
+                }
+                apns.payload = Some(payload);
+            }
+        }
+
+        if !self.excluded_platforms.contains(&Platform::Web) {
+            let webpush = self.webpush.get_or_insert_with(WebpushConfig::default);
+            let mut headers = webpush.headers.take().unwrap_or_else(|| Value::Object(Map::new()));
+            if let Value::Object(headers) = &mut headers {
+                headers.insert(
+                    "Urgency".to_string(),
+                    serde_json::to_value(priority.webpush()).unwrap_or_default(),
+                );
+            }
+            webpush.headers = Some(headers);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::message::Message;
+
+/// Default data key for the schema version of the data payload.
+pub const DEFAULT_SCHEMA_VERSION_KEY: &str = "schema_version";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    #[error("Data payload does not contain schema version key {0:?}")]
+    MissingVersion(String),
+    #[error("Schema version is not a valid number: {0:?}")]
+    InvalidVersion(String),
+    #[error("No migration from schema version {0}")]
+    MissingMigration(u32),
+    #[error("Schema version {from} is newer than the target version {to}")]
+    Downgrade { from: u32, to: u32 },
+}
+
+impl Message {
+    /// Store `version` in the data payload under `key`, replacing the
+    /// previous version.
+    pub fn set_schema_version(&mut self, key: &str, version: u32) {
+        let data = self.data.get_or_insert_with(|| Value::Object(Default::default()));
+        if let Value::Object(map) = data {
+            map.insert(key.to_string(), Value::String(version.to_string()));
+        }
+    }
+
+    /// Schema version stored in the data payload under `key`.
+    pub fn schema_version(&self, key: &str) -> Result<u32, SchemaError> {
+        let value = self
+            .data
+            .as_ref()
+            .and_then(|data| data.get(key))
+            .ok_or_else(|| SchemaError::MissingVersion(key.to_string()))?;
+        let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+        value.parse().map_err(|_| SchemaError::InvalidVersion(value))
+    }
+}
+
+/// Insert `version` to `data` under `key` unless the key already exists.
+pub(crate) fn tag_schema_version(data: &mut Option<Value>, key: &str, version: u32) {
+    let data = data.get_or_insert_with(|| Value::Object(Default::default()));
+    if let Value::Object(map) = data {
+        map.entry(key).or_insert_with(|| Value::String(version.to_string()));
+    }
+}
+
+type Migration = Box<dyn Fn(&mut Message) + Send + Sync>;
+
+/// Up-converts stored messages from older data payload schema versions.
+///
+/// ```rust
+/// use fcm::message::{Message, SchemaMigrations, Target, DEFAULT_SCHEMA_VERSION_KEY};
+/// use serde_json::json;
+///
+/// let migrations = SchemaMigrations::new(DEFAULT_SCHEMA_VERSION_KEY)
+///     // Version 1 used "id", version 2 uses "order_id".
+///     .add(1, |message| {
+///         if let Some(data) = message.data.as_mut().and_then(|data| data.as_object_mut()) {
+///             if let Some(id) = data.remove("id") {
+///                 data.insert("order_id".to_string(), id);
+///             }
+///         }
+///     });
+///
+/// let mut message = Message::builder(Target::Topic("orders".to_string()))
+///     .data(json!({ "schema_version": "1", "id": "42" }))
+///     .build();
+///
+/// migrations.migrate(&mut message, 2).unwrap();
+/// assert_eq!(Some(json!({ "schema_version": "2", "order_id": "42" })), message.data);
+/// ```
+pub struct SchemaMigrations {
+    key: String,
+    migrations: BTreeMap<u32, Migration>,
+}
+
+impl fmt::Debug for SchemaMigrations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SchemaMigrations")
+            .field("key", &self.key)
+            .field("versions", &self.migrations.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl SchemaMigrations {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            migrations: BTreeMap::new(),
+        }
+    }
+
+    /// Add migration from schema version `from_version` to `from_version + 1`.
+    /// The version number in the data payload is updated automatically.
+    pub fn add(mut self, from_version: u32, migration: impl Fn(&mut Message) + Send + Sync + 'static) -> Self {
+        self.migrations.insert(from_version, Box::new(migration));
+        self
+    }
+
+    /// Migrate `message` to `target_version` one version at a time. Returns
+    /// the original schema version of the message.
+    pub fn migrate(&self, message: &mut Message, target_version: u32) -> Result<u32, SchemaError> {
+        let original_version = message.schema_version(&self.key)?;
+        if original_version > target_version {
+            return Err(SchemaError::Downgrade {
+                from: original_version,
+                to: target_version,
+            });
+        }
+        if let Some(version) = (original_version..target_version).find(|v| !self.migrations.contains_key(v)) {
+            return Err(SchemaError::MissingMigration(version));
+        }
+        for version in original_version..target_version {
+            self.migrations[&version](message);
+            if let Some(Value::Object(data)) = &mut message.data {
+                data.insert(self.key.clone(), Value::String((version + 1).to_string()));
+            }
+        }
+        Ok(original_version)
+    }
+}
@@ -0,0 +1,61 @@
+use serde::Serialize;
+
+use crate::message::{Message, Platform};
+
+/// Maximum payload size in bytes which FCM accepts for a platform.
+/// <https://firebase.google.com/docs/cloud-messaging/concept-options#notifications_and_data_messages>
+pub const MAX_PAYLOAD_SIZE: usize = 4096;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PayloadSizeError {
+    #[error("{platform:?} payload is {size} bytes, maximum is {limit} bytes")]
+    TooLarge {
+        platform: Platform,
+        size: usize,
+        limit: usize,
+    },
+}
+
+fn json_len<T: Serialize>(value: &Option<T>) -> usize {
+    value
+        .as_ref()
+        .and_then(|value| serde_json::to_vec(value).ok())
+        .map_or(0, |json| json.len())
+}
+
+impl Message {
+    /// Estimated size in bytes of the payload delivered to `platform`.
+    ///
+    /// The estimate is the serialized size of the common `notification`
+    /// and `data` plus the platform specific notification and data. Fields
+    /// which override each other are counted twice, so the estimate is
+    /// larger than or equal to the real payload size.
+    pub fn payload_size(&self, platform: Platform) -> usize {
+        let common = json_len(&self.notification) + json_len(&self.data);
+        let specific = match platform {
+            Platform::Android => self
+                .android
+                .as_ref()
+                .map_or(0, |android| json_len(&android.notification) + json_len(&android.data)),
+            Platform::Ios => self.apns.as_ref().map_or(0, |apns| json_len(&apns.payload)),
+            Platform::Web => self
+                .webpush
+                .as_ref()
+                .map_or(0, |webpush| json_len(&webpush.notification) + json_len(&webpush.data)),
+        };
+        common + specific
+    }
+
+    /// Check that [Message::payload_size] of every platform is at most
+    /// `limit` bytes, for example [MAX_PAYLOAD_SIZE]. A lower limit can be
+    /// used to warn about messages which are close to the maximum.
+    pub fn validate_payload_size(&self, limit: usize) -> Result<(), PayloadSizeError> {
+        for platform in Platform::ALL {
+            let size = self.payload_size(platform);
+            if size > limit {
+                return Err(PayloadSizeError::TooLarge { platform, size, limit });
+            }
+        }
+        Ok(())
+    }
+}
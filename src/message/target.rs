@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Target to send a message to.
 ///
@@ -9,10 +9,18 @@ use serde::Serialize;
 /// Target::Topic("my-topic-name".to_string());
 /// Target::Condition("my-condition".to_string());
 /// ```
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Target {
     Token(String),
     Topic(String),
     Condition(String),
 }
+
+impl Target {
+    /// Target a device group by its notification key. FCM accepts
+    /// notification keys in place of registration tokens.
+    pub fn device_group(notification_key: impl Into<String>) -> Self {
+        Target::Token(notification_key.into())
+    }
+}
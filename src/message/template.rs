@@ -0,0 +1,129 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::message::{Message, Target};
+
+#[derive(thiserror::Error, Debug)]
+pub enum TemplateError {
+    #[error("Template parameters serialization failed")]
+    Serialization(#[source] serde_json::Error),
+    #[error("Template parameters must serialize to a JSON object")]
+    NotAnObject,
+    #[error("Template parameter {0:?} is missing")]
+    MissingParameter(String),
+    #[error("Rendered message is not valid")]
+    InvalidMessage(#[source] serde_json::Error),
+}
+
+/// A message with `{{name}}` placeholders which are replaced with
+/// parameters when the message is rendered.
+///
+/// Placeholders are replaced in every string value of the message, such as
+/// the notification title and body, data values and the platform specific
+/// fields. Keys are never replaced.
+///
+/// ```rust
+/// use fcm::message::{Message, MessageTemplate, Notification, Target};
+/// use serde_json::json;
+///
+/// let template = MessageTemplate::new(
+///     Message::builder(Target::Topic("unused".to_string()))
+///         .notification(Notification {
+///             title: Some("Hi {{username}}".to_string()),
+///             ..Default::default()
+///         })
+///         .data_entry("order_id", "{{order_id}}")
+///         .build(),
+/// );
+///
+/// let message = template
+///     .render_for(
+///         Target::Token("token".to_string()),
+///         &json!({ "username": "Ann", "order_id": 42 }),
+///     )
+///     .unwrap();
+/// assert_eq!(message.notification.unwrap().title.unwrap(), "Hi Ann");
+/// assert_eq!(message.data.unwrap()["order_id"], "42");
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageTemplate {
+    message: Value,
+}
+
+impl MessageTemplate {
+    pub fn new(message: Message) -> Self {
+        // Message only has string keyed maps and serializers which do not
+        // fail, so converting it to a Value can not fail.
+        Self {
+            message: serde_json::to_value(&message).expect("Message serializes to JSON"),
+        }
+    }
+
+    /// Render the template for its own target. `params` must serialize to
+    /// a JSON object, numbers and booleans are converted to strings.
+    pub fn render<T: Serialize + ?Sized>(&self, params: &T) -> Result<Message, TemplateError> {
+        let params = template_params(params)?;
+        let mut message = self.message.clone();
+        render_value(&mut message, &params)?;
+        serde_json::from_value(message).map_err(TemplateError::InvalidMessage)
+    }
+
+    /// Render the template and send it to `target` instead of the target of
+    /// the template.
+    pub fn render_for<T: Serialize + ?Sized>(&self, target: Target, params: &T) -> Result<Message, TemplateError> {
+        let mut message = self.render(params)?;
+        message.target = target;
+        Ok(message)
+    }
+
+    /// Render one message per recipient, for example to pass to
+    /// [crate::FcmClient::send_each].
+    pub fn render_each<'a, I, T>(&'a self, recipients: I) -> impl Iterator<Item = Result<Message, TemplateError>> + 'a
+    where
+        I: IntoIterator<Item = (Target, T)>,
+        I::IntoIter: 'a,
+        T: Serialize,
+    {
+        recipients
+            .into_iter()
+            .map(move |(target, params)| self.render_for(target, &params))
+    }
+}
+
+fn template_params<T: Serialize + ?Sized>(params: &T) -> Result<Map<String, Value>, TemplateError> {
+    match serde_json::to_value(params).map_err(TemplateError::Serialization)? {
+        Value::Object(params) => Ok(params),
+        _ => Err(TemplateError::NotAnObject),
+    }
+}
+
+fn render_value(value: &mut Value, params: &Map<String, Value>) -> Result<(), TemplateError> {
+    match value {
+        Value::String(text) if text.contains("{{") => *text = render_str(text, params)?,
+        Value::Array(values) => values.iter_mut().try_for_each(|value| render_value(value, params))?,
+        Value::Object(values) => values.values_mut().try_for_each(|value| render_value(value, params))?,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn render_str(text: &str, params: &Map<String, Value>) -> Result<String, TemplateError> {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => break,
+        };
+        let name = rest[start + 2..end].trim();
+        rendered.push_str(&rest[..start]);
+        match params.get(name) {
+            Some(Value::String(value)) => rendered.push_str(value),
+            Some(Value::Null) | None => return Err(TemplateError::MissingParameter(name.to_string())),
+            Some(value) => rendered.push_str(&value.to_string()),
+        }
+        rest = &rest[end + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
@@ -1,8 +1,15 @@
 use crate::{
-    message::{Message, Target},
+    message::{
+        object_entry, AnalyticsLabel, AnalyticsLabelError, AndroidConfig, AndroidFcmOptions, AndroidMessagePriority,
+        AndroidNotification, ApnsConfig, ApnsFcmOptions, Aps, ApsAlert, Condition, ConditionError, DataError,
+        DeliveryPriority, FcmOptions, LocalizedNotification, Message, MessageTemplate, PayloadSizeError, Platform,
+        PlatformError, ProfiledMessage, SchemaError, SchemaMigrations, Target, TemplateError, Topic, WebpushConfig,
+        MAX_PAYLOAD_SIZE,
+    },
     notification::Notification,
 };
 use serde_json::json;
+use std::time::Duration;
 
 #[test]
 fn should_create_new_message() {
@@ -15,6 +22,7 @@ fn should_create_new_message() {
         webpush: None,
         apns: None,
         fcm_options: None,
+        excluded_platforms: Vec::new(),
     };
 
     assert_eq!(msg.target, target);
@@ -31,6 +39,7 @@ fn should_leave_nones_out_of_the_json() {
         webpush: None,
         apns: None,
         fcm_options: None,
+        excluded_platforms: Vec::new(),
     };
     let payload = serde_json::to_string(&msg).unwrap();
 
@@ -55,6 +64,7 @@ fn should_add_custom_data_to_the_payload() {
         webpush: None,
         apns: None,
         fcm_options: None,
+        excluded_platforms: Vec::new(),
     };
 
     let payload = serde_json::to_string(&msg).unwrap();
@@ -87,6 +97,7 @@ fn should_be_able_to_render_a_full_token_message_to_json() {
         webpush: None,
         apns: None,
         fcm_options: None,
+        excluded_platforms: Vec::new(),
     };
 
     let payload = serde_json::to_string(&msg).unwrap();
@@ -116,6 +127,7 @@ fn should_be_able_to_render_a_full_topic_message_to_json() {
         webpush: None,
         apns: None,
         fcm_options: None,
+        excluded_platforms: Vec::new(),
     };
 
     let payload = serde_json::to_string(&msg).unwrap();
@@ -145,6 +157,7 @@ fn should_be_able_to_render_a_full_condition_message_to_json() {
         webpush: None,
         apns: None,
         fcm_options: None,
+        excluded_platforms: Vec::new(),
     };
 
     let payload = serde_json::to_string(&msg).unwrap();
@@ -176,7 +189,603 @@ fn should_set_notifications() {
         webpush: None,
         apns: None,
         fcm_options: None,
+        excluded_platforms: Vec::new(),
     };
 
     assert!(msg.notification.is_some());
 }
+
+#[test]
+fn should_build_message_with_builder() {
+    let msg = Message::builder(Target::Token("token".to_string()))
+        .data_entry("foo", "bar")
+        .data_entry("baz", "qux")
+        .build();
+
+    let payload = serde_json::to_string(&msg).unwrap();
+
+    let expected_payload = json!({
+        "data": {
+            "foo": "bar",
+            "baz": "qux",
+        },
+        "token": "token"
+    })
+    .to_string();
+
+    assert_eq!(expected_payload, payload);
+}
+
+#[test]
+fn should_build_message_with_macro() {
+    let msg = crate::message! {
+        topic: "news",
+        notification: {
+            title: "Hello",
+            body: "World",
+        },
+        data: {
+            "article_id": "42",
+        },
+    };
+
+    let payload = serde_json::to_string(&msg).unwrap();
+
+    let expected_payload = json!({
+        "data": {
+            "article_id": "42",
+        },
+        "notification": {
+            "title": "Hello",
+            "body": "World",
+        },
+        "topic": "news"
+    })
+    .to_string();
+
+    assert_eq!(expected_payload, payload);
+}
+
+#[test]
+fn should_render_condition_with_operators() {
+    let condition = Topic::new("dogs") & (Topic::new("food") | Topic::new("toys"));
+
+    assert_eq!(
+        Target::Condition("'dogs' in topics && ('food' in topics || 'toys' in topics)".to_string()),
+        condition.build().unwrap()
+    );
+}
+
+#[test]
+fn should_render_condition_with_methods_and_negation() {
+    let condition = Topic::new("a").or(Topic::new("b")).and(!Topic::new("c"));
+
+    assert_eq!(
+        "('a' in topics || 'b' in topics) && !('c' in topics)",
+        condition.to_string()
+    );
+    assert_eq!(
+        "!('a' in topics && 'b' in topics)",
+        (!(Topic::new("a") & Topic::new("b"))).to_string()
+    );
+}
+
+#[test]
+fn should_reject_condition_with_too_many_topics() {
+    let condition: Condition = ["a", "b", "c", "d", "e", "f"]
+        .iter()
+        .map(|name| Condition::from(Topic::new(*name)))
+        .reduce(|acc, topic| acc | topic)
+        .unwrap();
+
+    assert_eq!(Err(ConditionError::TooManyTopics { count: 6 }), condition.build());
+}
+
+#[test]
+fn should_reject_condition_with_invalid_topic_name() {
+    let condition = Topic::new("dogs") & Topic::new("cats' in topics");
+
+    assert_eq!(
+        Err(ConditionError::InvalidTopicName("cats' in topics".to_string())),
+        condition.build()
+    );
+}
+
+#[derive(serde::Serialize)]
+struct OrderUpdate {
+    order_id: u64,
+    shipped: bool,
+    status: &'static str,
+    items: Vec<&'static str>,
+    note: Option<String>,
+}
+
+#[test]
+fn should_coerce_data_from_serializable_to_strings() {
+    let update = OrderUpdate {
+        order_id: 42,
+        shipped: true,
+        status: "in_transit",
+        items: vec!["book", "pen"],
+        note: None,
+    };
+    let msg = Message::builder(Target::Token("token".to_string()))
+        .data_from(&update)
+        .unwrap()
+        .build();
+
+    let expected_data = json!({
+        "order_id": "42",
+        "shipped": "true",
+        "status": "in_transit",
+        "items": "[\"book\",\"pen\"]",
+    });
+
+    assert_eq!(Some(expected_data), msg.data);
+}
+
+#[test]
+fn should_reject_reserved_data_keys() {
+    let mut msg = Message::builder(Target::Token("token".to_string())).build();
+
+    let result = msg.data_from(&json!({ "google.c.a.e": "1" }));
+    assert!(matches!(result, Err(DataError::ReservedKey(key)) if key == "google.c.a.e"));
+
+    let result = msg.data_from(&json!({ "from": "me" }));
+    assert!(matches!(result, Err(DataError::ReservedKey(key)) if key == "from"));
+}
+
+#[test]
+fn should_reject_data_which_is_not_an_object() {
+    let mut msg = Message::builder(Target::Token("token".to_string())).build();
+
+    assert!(matches!(msg.data_from(&vec![1, 2]), Err(DataError::NotAnObject)));
+}
+
+#[test]
+fn should_report_invalid_data_bytes_precisely() {
+    let result = crate::message::data_from_bytes([("title", &b"caf\xc3"[..])]);
+    assert!(matches!(
+        result,
+        Err(DataError::InvalidUtf8Value { key, valid_up_to: 3 }) if key == "title"
+    ));
+
+    let result = crate::message::data_from_bytes([(&b"\xffkey"[..], "value")]);
+    assert!(matches!(result, Err(DataError::InvalidUtf8Key { valid_up_to: 0, .. })));
+
+    let result = crate::message::data_from_bytes([("title", "a\0b")]);
+    assert!(matches!(
+        result,
+        Err(DataError::NulInValue { key, position: 1 }) if key == "title"
+    ));
+
+    let data = crate::message::data_from_bytes([("title", "café")]).unwrap();
+    assert_eq!(data, json!({ "title": "café" }));
+}
+
+#[test]
+fn should_validate_data_payload() {
+    assert!(crate::message::validate_data(&json!({ "title": "hello" })).is_ok());
+    assert!(matches!(
+        crate::message::validate_data(&json!({ "count": 1 })),
+        Err(DataError::NonStringValue(key)) if key == "count"
+    ));
+    assert!(matches!(
+        crate::message::validate_data(&json!({ "ke\0y": "v" })),
+        Err(DataError::NulInKey { position: 2, .. })
+    ));
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn should_normalize_data_to_nfc() {
+    let mut data = json!({ "cafe\u{301}": "e\u{301}" });
+
+    crate::message::normalize_data_nfc(&mut data);
+
+    assert_eq!(data, json!({ "caf\u{e9}": "\u{e9}" }));
+}
+
+#[test]
+fn should_set_and_read_schema_version() {
+    let mut msg = Message::builder(Target::Token("token".to_string()))
+        .data_entry("foo", "bar")
+        .build();
+
+    msg.set_schema_version("v", 3);
+
+    assert_eq!(Ok(3), msg.schema_version("v"));
+    assert_eq!(Some(json!({ "foo": "bar", "v": "3" })), msg.data);
+    assert_eq!(
+        Err(SchemaError::MissingVersion("other".to_string())),
+        msg.schema_version("other")
+    );
+}
+
+#[test]
+fn should_migrate_schema_versions_in_order() {
+    let migrations = SchemaMigrations::new("v")
+        .add(1, |msg| msg.data.as_mut().unwrap()["steps"] = json!("1"))
+        .add(2, |msg| msg.data.as_mut().unwrap()["steps"] = json!("1,2"));
+    let mut msg = Message::builder(Target::Token("token".to_string()))
+        .data_entry("v", "1")
+        .build();
+
+    assert_eq!(Ok(1), migrations.migrate(&mut msg, 3));
+    assert_eq!(Some(json!({ "v": "3", "steps": "1,2" })), msg.data);
+    assert_eq!(
+        Err(SchemaError::Downgrade { from: 3, to: 2 }),
+        migrations.migrate(&mut msg, 2)
+    );
+}
+
+#[test]
+fn should_not_migrate_partially_when_migration_is_missing() {
+    let migrations = SchemaMigrations::new("v").add(1, |msg| msg.data = None);
+    let mut msg = Message::builder(Target::Token("token".to_string()))
+        .data_entry("v", "1")
+        .build();
+
+    assert_eq!(Err(SchemaError::MissingMigration(2)), migrations.migrate(&mut msg, 3));
+    assert_eq!(Some(json!({ "v": "1" })), msg.data);
+}
+
+#[test]
+fn should_strip_configuration_of_excluded_platforms() {
+    let mut msg = Message::builder(Target::Token("token".to_string()))
+        .notification(Notification::builder().title("Sale").build())
+        .android(AndroidConfig::default())
+        .apns(ApnsConfig::default())
+        .webpush(WebpushConfig::default())
+        .build();
+
+    msg.platforms(&[Platform::Android, Platform::Ios]).unwrap();
+
+    assert!(msg.android.is_some());
+    assert!(msg.apns.is_some());
+    assert!(msg.webpush.is_none());
+    assert_eq!(Err(PlatformError::NoPlatforms), msg.platforms(&[]));
+}
+
+#[test]
+fn should_not_add_priority_to_excluded_platforms() {
+    let mut msg = Message::builder(Target::Token("token".to_string()))
+        .notification(Notification::builder().title("Sale").build())
+        .build();
+
+    msg.platforms(&[Platform::Android]).unwrap();
+    msg.set_priority(DeliveryPriority::High);
+
+    assert_eq!(vec![Platform::Ios, Platform::Web], msg.excluded_platforms);
+    assert!(msg.android.is_some());
+    assert!(msg.apns.is_none());
+    assert!(msg.webpush.is_none());
+}
+
+#[test]
+fn should_reject_excluding_platforms_from_shared_topic_payload() {
+    let mut msg = Message::builder(Target::Topic("news".to_string()))
+        .data_entry("id", "1")
+        .webpush(WebpushConfig::default())
+        .build();
+
+    assert_eq!(
+        Err(PlatformError::SharedPayload(Platform::Web)),
+        msg.platforms(&[Platform::Android, Platform::Ios])
+    );
+    assert!(msg.webpush.is_some());
+    assert_eq!(Ok(()), msg.platforms(&Platform::ALL));
+}
+
+#[test]
+fn should_include_platform_payload_in_payload_size() {
+    let msg = Message::builder(Target::Token("token".to_string()))
+        .data_entry("body", "x".repeat(3000))
+        .webpush(WebpushConfig {
+            data: Some(json!({ "extra": "x".repeat(1500) })),
+            ..Default::default()
+        })
+        .build();
+
+    assert!(msg.payload_size(Platform::Android) < MAX_PAYLOAD_SIZE);
+    assert!(msg.payload_size(Platform::Web) > msg.payload_size(Platform::Android) + 1500);
+
+    let error = msg.validate_payload_size(MAX_PAYLOAD_SIZE).unwrap_err();
+    assert!(matches!(
+        error,
+        PayloadSizeError::TooLarge {
+            platform: Platform::Web,
+            ..
+        }
+    ));
+    assert!(msg.validate_payload_size(8192).is_ok());
+}
+
+#[test]
+fn should_localize_android_and_apns_from_one_call() {
+    let msg = Message::builder(Target::Token("token".to_string()))
+        .android(AndroidConfig {
+            notification: Some(AndroidNotification::builder().channel_id("orders").build()),
+            ..Default::default()
+        })
+        .apns(
+            ApnsConfig::builder()
+                .aps(
+                    Aps::builder()
+                        .alert(ApsAlert::builder().subtitle("Orders").build())
+                        .build(),
+                )
+                .custom_data("order_id", "1234")
+                .build(),
+        )
+        .localized(
+            LocalizedNotification::new()
+                .title("shipped_title", vec![])
+                .body("shipped_body", vec!["1234".to_string()]),
+        )
+        .build();
+
+    let payload = serde_json::to_value(&msg).unwrap();
+
+    assert_eq!(
+        json!({
+            "channel_id": "orders",
+            "body_loc_key": "shipped_body",
+            "body_loc_args": ["1234"],
+            "title_loc_key": "shipped_title",
+            "title_loc_args": [],
+        }),
+        payload["android"]["notification"]
+    );
+    assert_eq!(
+        json!({
+            "order_id": "1234",
+            "aps": {
+                "alert": {
+                    "subtitle": "Orders",
+                    "title-loc-key": "shipped_title",
+                    "title-loc-args": [],
+                    "loc-key": "shipped_body",
+                    "loc-args": ["1234"],
+                },
+            },
+        }),
+        payload["apns"]["payload"]
+    );
+}
+
+#[test]
+fn should_keep_string_alert_as_body_when_localizing() {
+    let mut config = ApnsConfig {
+        payload: Some(json!({ "aps": { "alert": "Your order has shipped" } })),
+        ..Default::default()
+    };
+
+    LocalizedNotification::new()
+        .title("shipped_title", vec![])
+        .apply_apns(&mut config);
+
+    assert_eq!(
+        Some(json!({
+            "aps": {
+                "alert": {
+                    "body": "Your order has shipped",
+                    "title-loc-key": "shipped_title",
+                    "title-loc-args": [],
+                },
+            },
+        })),
+        config.payload
+    );
+}
+
+#[test]
+fn should_replace_non_object_entries_of_serialized_message() {
+    let mut message = json!({ "apns": { "headers": "invalid" }, "webpush": { "headers": { "TTL": "60" } } });
+    let message = message.as_object_mut().unwrap();
+
+    object_entry(object_entry(message, "apns"), "headers").insert("apns-priority".to_string(), "5".into());
+    object_entry(object_entry(message, "webpush"), "headers").insert("Urgency".to_string(), "high".into());
+
+    assert_eq!(json!({ "apns-priority": "5" }), message["apns"]["headers"]);
+    assert_eq!(json!({ "TTL": "60", "Urgency": "high" }), message["webpush"]["headers"]);
+}
+
+#[test]
+fn should_validate_analytics_label() {
+    assert_eq!(Err(AnalyticsLabelError::Empty), AnalyticsLabel::new(""));
+    assert_eq!(
+        Err(AnalyticsLabelError::InvalidCharacter(' ')),
+        AnalyticsLabel::new("spring sale")
+    );
+    assert_eq!(
+        Err(AnalyticsLabelError::InvalidCharacter('ä')),
+        AnalyticsLabel::new("kevät")
+    );
+    assert_eq!(
+        Err(AnalyticsLabelError::TooLong { length: 51 }),
+        AnalyticsLabel::new("a".repeat(51))
+    );
+    assert!(AnalyticsLabel::new("a".repeat(50)).is_ok());
+}
+
+#[test]
+fn should_serialize_analytics_label_on_every_level() {
+    let label = AnalyticsLabel::new("spring_sale-2024.v1~%20").unwrap();
+    let msg = Message::builder(Target::Topic("news".to_string()))
+        .fcm_options(FcmOptions::new(label.clone()))
+        .android(AndroidConfig {
+            fcm_options: Some(AndroidFcmOptions::new(label.clone())),
+            ..Default::default()
+        })
+        .apns(
+            ApnsConfig::builder()
+                .fcm_options(ApnsFcmOptions {
+                    analytics_label: Some(label.clone()),
+                    image: None,
+                })
+                .build(),
+        )
+        .webpush(WebpushConfig::builder().analytics_label(label).build())
+        .build();
+
+    let payload = serde_json::to_value(&msg).unwrap();
+
+    for pointer in [
+        "/fcm_options/analytics_label",
+        "/android/fcm_options/analytics_label",
+        "/apns/fcm_options/analytics_label",
+        "/webpush/fcm_options/analytics_label",
+    ] {
+        assert_eq!(Some(&json!("spring_sale-2024.v1~%20")), payload.pointer(pointer));
+    }
+}
+
+#[test]
+fn should_set_priority_on_every_platform() {
+    let mut msg = Message::builder(Target::Token("token".to_string()))
+        .apns(ApnsConfig {
+            headers: Some(json!({ "apns-priority": "10", "apns-push-type": "background" })),
+            ..ApnsConfig::default()
+        })
+        .build();
+
+    msg.set_priority(DeliveryPriority::Background);
+
+    let payload = serde_json::to_value(&msg).unwrap();
+    assert_eq!(Some(&json!("NORMAL")), payload.pointer("/android/priority"));
+    assert_eq!(
+        Some(&json!({ "apns-priority": "5", "apns-push-type": "background" })),
+        payload.pointer("/apns/headers")
+    );
+    assert_eq!(Some(&json!("very-low")), payload.pointer("/webpush/headers/Urgency"));
+
+    msg.set_priority(DeliveryPriority::Transactional);
+
+    // Background notifications keep APNs priority 5.
+    let payload = serde_json::to_value(&msg).unwrap();
+    assert_eq!(Some(&json!("HIGH")), payload.pointer("/android/priority"));
+    assert_eq!(Some(&json!("5")), payload.pointer("/apns/headers/apns-priority"));
+    assert_eq!(Some(&json!("high")), payload.pointer("/webpush/headers/Urgency"));
+
+    msg.apns = None;
+    msg.notification = Some(Notification::builder().title("Your code is 1234").build());
+    msg.set_priority(DeliveryPriority::Transactional);
+
+    let payload = serde_json::to_value(&msg).unwrap();
+    assert_eq!(Some(&json!("10")), payload.pointer("/apns/headers/apns-priority"));
+    assert_eq!(
+        Some(&json!("time-sensitive")),
+        payload.pointer("/apns/payload/aps/interruption-level")
+    );
+}
+
+#[test]
+fn should_keep_apns_priority_5_for_content_available() {
+    let mut msg = Message::builder(Target::Token("token".to_string()))
+        .apns(
+            ApnsConfig::builder()
+                .aps(Aps::builder().content_available(true).build())
+                .build(),
+        )
+        .build();
+
+    msg.set_priority(DeliveryPriority::High);
+
+    let payload = serde_json::to_value(&msg).unwrap();
+    assert_eq!(Some(&json!("5")), payload.pointer("/apns/headers/apns-priority"));
+    assert_eq!(None, payload.pointer("/apns/payload/aps/interruption-level"));
+}
+
+#[test]
+fn should_round_trip_message_through_json() {
+    let label = AnalyticsLabel::new("campaign").unwrap();
+    let msg = Message::builder(Target::Condition("'news' in topics".to_string()))
+        .notification(Notification::builder().title("Hello").build())
+        .data_entry("foo", "bar")
+        .android(
+            AndroidConfig::builder()
+                .priority(AndroidMessagePriority::High)
+                .ttl(Duration::from_millis(3500))
+                .notification(AndroidNotification::builder().channel_id("news").build())
+                .build(),
+        )
+        .apns(ApnsConfig::builder().aps(Aps::builder().badge(1).build()).build())
+        .webpush(WebpushConfig::builder().analytics_label(label.clone()).build())
+        .fcm_options(FcmOptions::new(label))
+        .build();
+
+    let json = serde_json::to_string(&msg).unwrap();
+    let deserialized: Message = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(msg, deserialized);
+    assert_eq!(json, serde_json::to_string(&deserialized).unwrap());
+
+    let profiled = msg.profile("x");
+    let json = serde_json::to_string(&profiled).unwrap();
+    let deserialized: ProfiledMessage = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(profiled, deserialized);
+    assert_eq!(deserialized.profile.as_deref(), Some("x"));
+}
+
+#[test]
+fn should_reject_invalid_analytics_label_when_deserializing() {
+    let json = json!({ "topic": "news", "fcm_options": { "analytics_label": "spring sale" } });
+
+    assert!(serde_json::from_value::<Message>(json).is_err());
+}
+
+#[test]
+fn should_render_template_for_each_recipient() {
+    let template = MessageTemplate::new(
+        Message::builder(Target::Topic("unused".to_string()))
+            .notification(Notification {
+                title: Some("Hi {{ username }}".to_string()),
+                body: Some("Order {{order_id}} has shipped".to_string()),
+                ..Default::default()
+            })
+            .data_entry("order_id", "{{order_id}}")
+            .build(),
+    );
+
+    let messages = template
+        .render_each(vec![
+            (
+                Target::Token("a".to_string()),
+                json!({ "username": "Ann", "order_id": 1 }),
+            ),
+            (
+                Target::Token("b".to_string()),
+                json!({ "username": "Bob", "order_id": "2" }),
+            ),
+        ])
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(messages[0].target, Target::Token("a".to_string()));
+    assert_eq!(
+        messages[0].notification.as_ref().unwrap().title.as_deref(),
+        Some("Hi Ann")
+    );
+    assert_eq!(
+        messages[1].notification.as_ref().unwrap().body.as_deref(),
+        Some("Order 2 has shipped")
+    );
+    assert_eq!(messages[0].data, Some(json!({ "order_id": "1" })));
+}
+
+#[test]
+fn should_reject_missing_template_parameter() {
+    let template = MessageTemplate::new(
+        Message::builder(Target::Topic("news".to_string()))
+            .data_entry("greeting", "Hi {{username}}")
+            .build(),
+    );
+
+    assert!(matches!(
+        template.render(&json!({})),
+        Err(TemplateError::MissingParameter(name)) if name == "username"
+    ));
+    assert!(matches!(template.render(&1), Err(TemplateError::NotAnObject)));
+}
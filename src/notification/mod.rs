@@ -1,9 +1,9 @@
 #[cfg(test)]
 mod tests;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 /// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages?authuser=0#notification>
 pub struct Notification {
     /// The notification's title.
@@ -18,3 +18,54 @@ pub struct Notification {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image: Option<String>,
 }
+
+impl Notification {
+    pub fn builder() -> NotificationBuilder {
+        NotificationBuilder::default()
+    }
+}
+
+/// Builder for [Notification].
+///
+/// ```rust
+/// use fcm::message::Notification;
+///
+/// let notification = Notification::builder()
+///     .title("New photo")
+///     .body("Alice shared a photo with you")
+///     .image("https://example.com/photo.jpg")
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct NotificationBuilder {
+    title: Option<String>,
+    body: Option<String>,
+    image: Option<String>,
+}
+
+impl NotificationBuilder {
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// URL of an image which is downloaded on the device and displayed
+    /// in the notification on all platforms.
+    pub fn image(mut self, image: impl Into<String>) -> Self {
+        self.image = Some(image.into());
+        self
+    }
+
+    pub fn build(self) -> Notification {
+        Notification {
+            title: self.title,
+            body: self.body,
+            image: self.image,
+        }
+    }
+}
@@ -20,3 +20,21 @@ fn should_be_able_to_render_a_full_notification_to_json() {
 
     assert_eq!(expected_payload, payload);
 }
+
+#[test]
+fn should_build_notification_with_image() {
+    let not = Notification::builder()
+        .title("foo")
+        .image("https://my.image.com/test.jpg")
+        .build();
+
+    let payload = serde_json::to_string(&not).unwrap();
+
+    let expected_payload = json!({
+        "title": "foo",
+        "image": "https://my.image.com/test.jpg",
+    })
+    .to_string();
+
+    assert_eq!(expected_payload, payload);
+}
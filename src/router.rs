@@ -0,0 +1,225 @@
+//! Routing of recipients to [Target]s.
+//!
+//! A [Router] asks its [AudienceResolver]s in order how to reach a
+//! [Recipient] and turns the first answer into targets, either one per
+//! registration token or a single topic:
+//!
+//! ```no_run
+//! use fcm::message::{Message, Notification};
+//! use fcm::{Audience, AudienceResolver, FcmClient, Recipient, ResolverError, Router};
+//! use futures_util::future::BoxFuture;
+//!
+//! struct Segments;
+//!
+//! impl AudienceResolver for Segments {
+//!     fn resolve<'a>(&'a self, recipient: &'a Recipient) -> BoxFuture<'a, Result<Option<Audience>, ResolverError>> {
+//!         Box::pin(async move {
+//!             Ok(match recipient {
+//!                 Recipient::Segment(segment) => Some(Audience::Topic(format!("segment-{}", segment))),
+//!                 Recipient::User(_) => None,
+//!             })
+//!         })
+//!     }
+//! }
+//!
+//! # async fn run(client: FcmClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let router = Router::builder().resolver(Segments).build();
+//! let response = client
+//!     .send_routed(&router, &Recipient::Segment("beta".to_string()), |target| {
+//!         Message::builder(target)
+//!             .notification(Notification {
+//!                 title: Some("New beta build".to_string()),
+//!                 ..Default::default()
+//!             })
+//!             .build()
+//!     })
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use futures_util::future::BoxFuture;
+
+use crate::message::{Message, Target};
+use crate::{BatchResponse, FcmClient};
+
+/// Error returned by an [AudienceResolver].
+pub type ResolverError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Who a message is meant for, independent of how FCM reaches them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Recipient {
+    /// A single user of the application.
+    User(String),
+    /// A group of users, such as beta testers or a country.
+    Segment(String),
+}
+
+/// How a [Recipient] is reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Audience {
+    /// Send to each registration token.
+    Tokens(Vec<String>),
+    /// Send once to a topic.
+    Topic(String),
+}
+
+impl Audience {
+    fn into_targets(self) -> Vec<Target> {
+        match self {
+            Audience::Tokens(tokens) => tokens.into_iter().map(Target::Token).collect(),
+            Audience::Topic(topic) => vec![Target::Topic(topic)],
+        }
+    }
+}
+
+/// Looks up the [Audience] of a [Recipient], for example in a database of
+/// registration tokens. Returns `None` if the resolver does not know the
+/// recipient, in which case the [Router] asks the next resolver.
+pub trait AudienceResolver: Send + Sync {
+    fn resolve<'a>(&'a self, recipient: &'a Recipient) -> BoxFuture<'a, Result<Option<Audience>, ResolverError>>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RouterError {
+    #[error("No resolver knows recipient {0:?}")]
+    Unresolved(Recipient),
+    #[error("Recipient {0:?} has no registration tokens")]
+    NoTokens(Recipient),
+    #[error("Resolver failed")]
+    Resolver(#[source] ResolverError),
+}
+
+/// Resolves [Recipient]s to [Target]s with a chain of [AudienceResolver]s.
+#[derive(Default)]
+pub struct Router {
+    resolvers: Vec<Box<dyn AudienceResolver>>,
+}
+
+impl Router {
+    pub fn builder() -> RouterBuilder {
+        RouterBuilder::default()
+    }
+
+    /// Resolve `recipient` with the first resolver which knows it.
+    pub async fn resolve(&self, recipient: &Recipient) -> Result<Audience, RouterError> {
+        for resolver in &self.resolvers {
+            match resolver.resolve(recipient).await.map_err(RouterError::Resolver)? {
+                Some(Audience::Tokens(tokens)) if tokens.is_empty() => {
+                    return Err(RouterError::NoTokens(recipient.clone()))
+                }
+                Some(audience) => return Ok(audience),
+                None => {}
+            }
+        }
+        Err(RouterError::Unresolved(recipient.clone()))
+    }
+
+    /// Resolve `recipient` to the targets to send to.
+    pub async fn targets(&self, recipient: &Recipient) -> Result<Vec<Target>, RouterError> {
+        Ok(self.resolve(recipient).await?.into_targets())
+    }
+}
+
+#[derive(Default)]
+pub struct RouterBuilder {
+    router: Router,
+}
+
+impl RouterBuilder {
+    /// Add a resolver. Resolvers are asked in the order they are added.
+    pub fn resolver(mut self, resolver: impl AudienceResolver + 'static) -> Self {
+        self.router.resolvers.push(Box::new(resolver));
+        self
+    }
+
+    pub fn build(self) -> Router {
+        self.router
+    }
+}
+
+impl FcmClient {
+    /// Resolve `recipient` with `router` and send the message created by
+    /// `message` to each resulting target.
+    pub async fn send_routed<F>(
+        &self,
+        router: &Router,
+        recipient: &Recipient,
+        message: F,
+    ) -> Result<BatchResponse, RouterError>
+    where
+        F: FnMut(Target) -> Message,
+    {
+        let messages: Vec<Message> = router.targets(recipient).await?.into_iter().map(message).collect();
+        Ok(self.send_each(messages).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Users;
+
+    impl AudienceResolver for Users {
+        fn resolve<'a>(&'a self, recipient: &'a Recipient) -> BoxFuture<'a, Result<Option<Audience>, ResolverError>> {
+            Box::pin(async move {
+                Ok(match recipient {
+                    Recipient::User(id) if id == "lonely" => Some(Audience::Tokens(vec![])),
+                    Recipient::User(id) => Some(Audience::Tokens(vec![
+                        format!("{}-phone", id),
+                        format!("{}-tablet", id),
+                    ])),
+                    Recipient::Segment(_) => None,
+                })
+            })
+        }
+    }
+
+    struct Segments;
+
+    impl AudienceResolver for Segments {
+        fn resolve<'a>(&'a self, recipient: &'a Recipient) -> BoxFuture<'a, Result<Option<Audience>, ResolverError>> {
+            Box::pin(async move {
+                match recipient {
+                    Recipient::Segment(segment) if segment == "broken" => Err("lookup failed".into()),
+                    Recipient::Segment(segment) => Ok(Some(Audience::Topic(segment.clone()))),
+                    Recipient::User(_) => Ok(None),
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_router_asks_resolvers_in_order() {
+        let router = Router::builder().resolver(Users).resolver(Segments).build();
+
+        assert_eq!(
+            router.targets(&Recipient::User("ann".to_string())).await.unwrap(),
+            vec![
+                Target::Token("ann-phone".to_string()),
+                Target::Token("ann-tablet".to_string())
+            ]
+        );
+        assert_eq!(
+            router.targets(&Recipient::Segment("beta".to_string())).await.unwrap(),
+            vec![Target::Topic("beta".to_string())]
+        );
+        assert!(matches!(
+            router.targets(&Recipient::User("lonely".to_string())).await,
+            Err(RouterError::NoTokens(_))
+        ));
+        assert!(matches!(
+            router.targets(&Recipient::Segment("broken".to_string())).await,
+            Err(RouterError::Resolver(_))
+        ));
+        assert!(matches!(
+            Router::builder()
+                .resolver(Users)
+                .build()
+                .targets(&Recipient::Segment("beta".to_string()))
+                .await,
+            Err(RouterError::Unresolved(_))
+        ));
+    }
+}
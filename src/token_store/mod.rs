@@ -0,0 +1,89 @@
+//! Storage of registration tokens by user.
+//!
+//! Tokens which FCM reports as invalid should be pruned so that they are
+//! not sent to again. [PruneReason::from_response] tells which responses
+//! mean that:
+//!
+//! ```no_run
+//! use fcm::message::{Message, Target};
+//! use fcm::{FcmClient, PruneReason, TokenStore};
+//!
+//! # async fn run(client: FcmClient, store: &dyn TokenStore) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! for token in store.tokens("user-1").await? {
+//!     let message = Message::builder(Target::Token(token.clone())).build();
+//!     let response = client.send(&message).await?;
+//!     if let Some(reason) = PruneReason::from_response(&response) {
+//!         store.prune(&token, reason).await?;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "postgres")]
+pub(crate) mod postgres;
+
+#[cfg(test)]
+mod tests;
+
+use futures_util::future::BoxFuture;
+
+use crate::client::response::{FcmResponse, FcmResponseError};
+
+#[cfg(feature = "postgres")]
+pub use crate::token_store::postgres::*;
+
+/// Error returned by a [TokenStore].
+pub type TokenStoreError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Why a token was removed from a [TokenStore].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneReason {
+    /// FCM reported the token as unregistered, for example because the
+    /// app was uninstalled.
+    Unregistered,
+    /// The token belongs to another sender.
+    SenderIdMismatch,
+    /// Removed by the application, for example on logout.
+    Manual,
+}
+
+impl PruneReason {
+    /// Reason to prune the token which `response` was sent to, or `None` if
+    /// the token is still valid.
+    pub fn from_response(response: &FcmResponse) -> Option<Self> {
+        match response.error()? {
+            FcmResponseError::Unregistered => Some(Self::Unregistered),
+            FcmResponseError::SenderIdMismatch => Some(Self::SenderIdMismatch),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unregistered => "UNREGISTERED",
+            Self::SenderIdMismatch => "SENDER_ID_MISMATCH",
+            Self::Manual => "MANUAL",
+        }
+    }
+}
+
+impl std::fmt::Display for PruneReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Registration tokens of users. Implementations should soft-delete pruned
+/// tokens, so that pruning can be audited, and restore a token when it is
+/// registered again.
+pub trait TokenStore: Send + Sync {
+    /// Store `token` for `user_id`.
+    fn register<'a>(&'a self, user_id: &'a str, token: &'a str) -> BoxFuture<'a, Result<(), TokenStoreError>>;
+
+    /// Tokens of `user_id` which are not pruned.
+    fn tokens<'a>(&'a self, user_id: &'a str) -> BoxFuture<'a, Result<Vec<String>, TokenStoreError>>;
+
+    /// Mark `token` as deleted.
+    fn prune<'a>(&'a self, token: &'a str, reason: PruneReason) -> BoxFuture<'a, Result<(), TokenStoreError>>;
+}
@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use sqlx::PgPool;
+
+use crate::token_store::{PruneReason, TokenStore, TokenStoreError};
+
+/// Statements which create the `fcm_tokens` table, run by
+/// [PostgresTokenStore::migrate].
+pub const POSTGRES_TOKEN_STORE_SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS fcm_tokens (
+    token TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    deleted_at TIMESTAMPTZ,
+    delete_reason TEXT
+);
+CREATE INDEX IF NOT EXISTS fcm_tokens_active_user_id ON fcm_tokens (user_id) WHERE deleted_at IS NULL;
+";
+
+/// [TokenStore] in the Postgres table `fcm_tokens`. Pruned tokens keep
+/// their row with `deleted_at` and `delete_reason` set.
+#[derive(Debug, Clone)]
+pub struct PostgresTokenStore {
+    pool: PgPool,
+}
+
+impl PostgresTokenStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create the table if it does not exist. Applications which manage
+    /// their schema can use [POSTGRES_TOKEN_STORE_SCHEMA] instead.
+    pub async fn migrate(&self) -> Result<(), sqlx::Error> {
+        sqlx::raw_sql(POSTGRES_TOKEN_STORE_SCHEMA).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Delete rows of tokens which were pruned before `deleted_before`.
+    /// Returns the number of deleted rows.
+    pub async fn purge_pruned(&self, deleted_before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM fcm_tokens WHERE deleted_at < $1")
+            .bind(deleted_before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl TokenStore for PostgresTokenStore {
+    fn register<'a>(&'a self, user_id: &'a str, token: &'a str) -> BoxFuture<'a, Result<(), TokenStoreError>> {
+        Box::pin(async move {
+            sqlx::query(
+                "INSERT INTO fcm_tokens (token, user_id) VALUES ($1, $2) \
+                 ON CONFLICT (token) DO UPDATE SET user_id = EXCLUDED.user_id, updated_at = now(), \
+                 deleted_at = NULL, delete_reason = NULL",
+            )
+            .bind(token)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+            Ok(())
+        })
+    }
+
+    fn tokens<'a>(&'a self, user_id: &'a str) -> BoxFuture<'a, Result<Vec<String>, TokenStoreError>> {
+        Box::pin(async move {
+            let tokens = sqlx::query_scalar(
+                "SELECT token FROM fcm_tokens WHERE user_id = $1 AND deleted_at IS NULL ORDER BY updated_at DESC",
+            )
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+            Ok(tokens)
+        })
+    }
+
+    fn prune<'a>(&'a self, token: &'a str, reason: PruneReason) -> BoxFuture<'a, Result<(), TokenStoreError>> {
+        Box::pin(async move {
+            sqlx::query(
+                "UPDATE fcm_tokens SET deleted_at = now(), delete_reason = $2 \
+                 WHERE token = $1 AND deleted_at IS NULL",
+            )
+            .bind(token)
+            .bind(reason.as_str())
+            .execute(&self.pool)
+            .await?;
+            Ok(())
+        })
+    }
+}
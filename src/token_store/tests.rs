@@ -0,0 +1,18 @@
+use crate::client::response::FcmResponse;
+use crate::token_store::PruneReason;
+
+#[test]
+fn should_prune_only_invalid_tokens() {
+    let response = |http_status_code| FcmResponse::new(http_status_code, Default::default(), None);
+
+    assert_eq!(
+        PruneReason::from_response(&response(404)),
+        Some(PruneReason::Unregistered)
+    );
+    assert_eq!(
+        PruneReason::from_response(&response(403)),
+        Some(PruneReason::SenderIdMismatch)
+    );
+    assert_eq!(PruneReason::from_response(&response(503)), None);
+    assert_eq!(PruneReason::Manual.to_string(), "MANUAL");
+}
@@ -1,2 +1,7 @@
+pub mod web_notification;
 pub mod webpush_config;
 pub mod webpush_fcm_options;
+pub mod webpush_headers;
+
+#[cfg(test)]
+mod tests;
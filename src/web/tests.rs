@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use crate::message::{Urgency, WebNotification, WebNotificationAction, WebpushConfig, WebpushHeaders};
+use serde_json::json;
+
+#[test]
+fn should_render_typed_webpush_config() {
+    let config = WebpushConfig::builder()
+        .headers(
+            WebpushHeaders::builder()
+                .ttl(Duration::from_millis(90_500))
+                .urgency(Urgency::VeryLow)
+                .build(),
+        )
+        .notification(
+            WebNotification::builder()
+                .title("New message")
+                .icon("https://example.com/icon.png")
+                .badge("https://example.com/badge.png")
+                .tag("chat-1")
+                .renotify(true)
+                .require_interaction(true)
+                .silent(false)
+                .vibrate(vec![200, 100, 200])
+                .action(WebNotificationAction::new("reply", "Reply").icon("https://example.com/reply.png"))
+                .build(),
+        )
+        .link("https://example.com/chat/1")
+        .build();
+
+    let payload = serde_json::to_value(&config).unwrap();
+
+    let expected_payload = json!({
+        "headers": {
+            "TTL": "90",
+            "Urgency": "very-low",
+        },
+        "notification": {
+            "title": "New message",
+            "icon": "https://example.com/icon.png",
+            "badge": "https://example.com/badge.png",
+            "tag": "chat-1",
+            "renotify": true,
+            "requireInteraction": true,
+            "silent": false,
+            "vibrate": [200, 100, 200],
+            "actions": [
+                { "action": "reply", "title": "Reply", "icon": "https://example.com/reply.png" },
+            ],
+        },
+        "fcm_options": {
+            "link": "https://example.com/chat/1",
+        },
+    });
+
+    assert_eq!(expected_payload, payload);
+}
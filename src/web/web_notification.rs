@@ -0,0 +1,172 @@
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// Web Notification options which serialize into `webpush.notification`.
+/// <https://developer.mozilla.org/en-US/docs/Web/API/Notification/Notification>
+pub struct WebNotification {
+    /// The title of the notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// The body text of the notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+
+    /// URL of an icon to display in the notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+
+    /// URL of an image to represent the notification when there is not enough space to display it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub badge: Option<String>,
+
+    /// URL of an image to display in the notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+
+    /// Language of the notification as a BCP 47 language tag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+
+    /// Identifying tag used to replace an existing notification with the same tag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// Notify the user when a notification with the same tag is replaced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renotify: Option<bool>,
+
+    /// Keep the notification visible until the user clicks or dismisses it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_interaction: Option<bool>,
+
+    /// Show the notification without sound or vibration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub silent: Option<bool>,
+
+    /// Vibration pattern as alternating vibration and pause durations in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vibrate: Option<Vec<u32>>,
+
+    /// Actions which the user can choose from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actions: Option<Vec<WebNotificationAction>>,
+
+    /// Arbitrary data associated with the notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// <https://developer.mozilla.org/en-US/docs/Web/API/Notification/actions>
+pub struct WebNotificationAction {
+    /// Identifier of the action, passed to the service worker's `notificationclick` event.
+    pub action: String,
+
+    /// Text shown to the user.
+    pub title: String,
+
+    /// URL of an icon to display with the action.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+impl WebNotificationAction {
+    pub fn new(action: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            action: action.into(),
+            title: title.into(),
+            icon: None,
+        }
+    }
+
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+}
+
+impl WebNotification {
+    pub fn builder() -> WebNotificationBuilder {
+        WebNotificationBuilder::default()
+    }
+}
+
+/// Builder for [WebNotification].
+#[derive(Debug, Default)]
+pub struct WebNotificationBuilder {
+    notification: WebNotification,
+}
+
+impl WebNotificationBuilder {
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.notification.title = Some(title.into());
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.notification.body = Some(body.into());
+        self
+    }
+
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.notification.icon = Some(icon.into());
+        self
+    }
+
+    pub fn badge(mut self, badge: impl Into<String>) -> Self {
+        self.notification.badge = Some(badge.into());
+        self
+    }
+
+    pub fn image(mut self, image: impl Into<String>) -> Self {
+        self.notification.image = Some(image.into());
+        self
+    }
+
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.notification.lang = Some(lang.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.notification.tag = Some(tag.into());
+        self
+    }
+
+    pub fn renotify(mut self, renotify: bool) -> Self {
+        self.notification.renotify = Some(renotify);
+        self
+    }
+
+    pub fn require_interaction(mut self, require_interaction: bool) -> Self {
+        self.notification.require_interaction = Some(require_interaction);
+        self
+    }
+
+    pub fn silent(mut self, silent: bool) -> Self {
+        self.notification.silent = Some(silent);
+        self
+    }
+
+    pub fn vibrate(mut self, pattern: Vec<u32>) -> Self {
+        self.notification.vibrate = Some(pattern);
+        self
+    }
+
+    pub fn action(mut self, action: WebNotificationAction) -> Self {
+        self.notification.actions.get_or_insert_with(Vec::new).push(action);
+        self
+    }
+
+    pub fn data(mut self, data: Value) -> Self {
+        self.notification.data = Some(data);
+        self
+    }
+
+    pub fn build(self) -> WebNotification {
+        self.notification
+    }
+}
@@ -1,9 +1,13 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use super::webpush_fcm_options::WebpushFcmOptions;
+use crate::message::AnalyticsLabel;
 
-#[derive(Debug, Default, Serialize)]
+use super::{
+    web_notification::WebNotification, webpush_fcm_options::WebpushFcmOptions, webpush_headers::WebpushHeaders,
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 /// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages?authuser=0#webpushconfig>
 pub struct WebpushConfig {
     /// HTTP headers defined in webpush protocol.
@@ -23,3 +27,75 @@ pub struct WebpushConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fcm_options: Option<WebpushFcmOptions>,
 }
+
+impl WebpushConfig {
+    pub fn builder() -> WebpushConfigBuilder {
+        WebpushConfigBuilder::default()
+    }
+}
+
+/// Builder for [WebpushConfig] with typed headers and notification options.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use fcm::message::{Urgency, WebNotification, WebpushConfig, WebpushHeaders};
+///
+/// let config = WebpushConfig::builder()
+///     .headers(WebpushHeaders::builder().urgency(Urgency::High).ttl(Duration::from_secs(3600)).build())
+///     .notification(WebNotification::builder().title("Hello").icon("https://example.com/icon.png").build())
+///     .link("https://example.com/inbox")
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct WebpushConfigBuilder {
+    config: WebpushConfig,
+}
+
+impl WebpushConfigBuilder {
+    pub fn headers(mut self, headers: WebpushHeaders) -> Self {
+        self.config.headers = Some(serde_json::to_value(headers).unwrap_or(Value::Null));
+        self
+    }
+
+    /// Set headers as a JSON object, for headers which [WebpushHeaders]
+    /// does not support.
+    pub fn raw_headers(mut self, headers: Value) -> Self {
+        self.config.headers = Some(headers);
+        self
+    }
+
+    pub fn data(mut self, data: Value) -> Self {
+        self.config.data = Some(data);
+        self
+    }
+
+    pub fn notification(mut self, notification: WebNotification) -> Self {
+        self.config.notification = Some(serde_json::to_value(notification).unwrap_or(Value::Null));
+        self
+    }
+
+    /// Set notification options as a JSON object, for options which
+    /// [WebNotification] does not support.
+    pub fn raw_notification(mut self, notification: Value) -> Self {
+        self.config.notification = Some(notification);
+        self
+    }
+
+    /// The HTTPS link to open when the user clicks on the notification.
+    pub fn link(mut self, link: impl Into<String>) -> Self {
+        self.config.fcm_options.get_or_insert_with(Default::default).link = Some(link.into());
+        self
+    }
+
+    pub fn analytics_label(mut self, analytics_label: AnalyticsLabel) -> Self {
+        self.config
+            .fcm_options
+            .get_or_insert_with(Default::default)
+            .analytics_label = Some(analytics_label);
+        self
+    }
+
+    pub fn build(self) -> WebpushConfig {
+        self.config
+    }
+}
@@ -1,11 +1,15 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Serialize)]
+use crate::message::AnalyticsLabel;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 /// <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages?authuser=0#webpushfcmoptions>
 pub struct WebpushFcmOptions {
-    /// The link to open when the user clicks on the notification.
-    pub link: String,
+    /// The link to open when the user clicks on the notification. Must be an HTTPS URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
 
     /// Label associated with the message's analytics data.
-    pub analytics_label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analytics_label: Option<AnalyticsLabel>,
 }
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+/// Value of the `Urgency` header.
+/// <https://datatracker.ietf.org/doc/html/rfc8030#section-5.3>
+pub enum Urgency {
+    VeryLow,
+    Low,
+    Normal,
+    High,
+}
+
+fn serialize_seconds<S>(ttl: &Option<Duration>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match ttl {
+        Some(ttl) => s.serialize_str(&ttl.as_secs().to_string()),
+        None => s.serialize_none(),
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+/// Typed webpush protocol headers which serialize into `webpush.headers`.
+/// <https://datatracker.ietf.org/doc/html/rfc8030#section-5>
+pub struct WebpushHeaders {
+    /// How long the push service retains the message, in whole seconds.
+    #[serde(
+        rename = "TTL",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_seconds"
+    )]
+    pub ttl: Option<Duration>,
+
+    /// How urgent the message is for the user agent.
+    #[serde(rename = "Urgency", skip_serializing_if = "Option::is_none")]
+    pub urgency: Option<Urgency>,
+
+    /// Identifier used to replace a pending message with the same topic.
+    #[serde(rename = "Topic", skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+}
+
+impl WebpushHeaders {
+    pub fn builder() -> WebpushHeadersBuilder {
+        WebpushHeadersBuilder::default()
+    }
+}
+
+/// Builder for [WebpushHeaders].
+#[derive(Debug, Default)]
+pub struct WebpushHeadersBuilder {
+    headers: WebpushHeaders,
+}
+
+impl WebpushHeadersBuilder {
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.headers.ttl = Some(ttl);
+        self
+    }
+
+    pub fn urgency(mut self, urgency: Urgency) -> Self {
+        self.headers.urgency = Some(urgency);
+        self
+    }
+
+    pub fn topic(mut self, topic: impl Into<String>) -> Self {
+        self.headers.topic = Some(topic.into());
+        self
+    }
+
+    pub fn build(self) -> WebpushHeaders {
+        self.headers
+    }
+}